@@ -4,19 +4,45 @@ use crate::hand::Hand;
 use crate::limit_hand::LimitHands;
 use crate::yaku::Yaku;
 
-#[derive(Debug, PartialEq)]
-pub enum CalculatorErrors {
-    NoHan,
-    NoFu,
-    NoYaku,
+/// How tsumo payments are made in sanma (three-player mahjong).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanmaTsumo {
+    /// The share of the missing North player is not paid (tsumo-zon).
+    TsumoLoss,
+    /// The share of the missing North player is split evenly between the two paying players (hokuchaku).
+    NorthBisection,
 }
 
-impl std::fmt::Display for CalculatorErrors {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl SanmaTsumo {
+    /// Convert four-player payment amounts, calculated without any honba, to three-player payment amounts.
+    ///
+    /// Ron payments are unchanged, and each tsumo payment is rounded up to the nearest 100.
+    /// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::calc::SanmaTsumo;
+    /// use mahc::limit_hand::LimitHands;
+    ///
+    /// let mangan: Vec<u32> = LimitHands::Mangan.get_score().iter().map(|&x| x.into()).collect();
+    ///
+    /// assert_eq!(SanmaTsumo::TsumoLoss.payments(&mangan), vec![12000, 4000, 8000, 2000, 4000]);
+    /// assert_eq!(SanmaTsumo::NorthBisection.payments(&mangan), vec![12000, 6000, 8000, 3000, 5000]);
+    /// ```
+    pub fn payments(&self, scores: &[u32]) -> Vec<u32> {
         match self {
-            Self::NoHan => write!(f, "No han provided!"),
-            Self::NoFu => write!(f, "No fu provided!"),
-            Self::NoYaku => write!(f, "No Yaku!"),
+            Self::TsumoLoss => scores.to_vec(),
+            Self::NorthBisection => {
+                let round_up = |points: u32| points.div_ceil(100) * 100;
+                vec![
+                    scores[0],
+                    round_up(scores[1] + scores[1] / 2),
+                    scores[2],
+                    round_up(scores[3] + scores[3] / 2),
+                    round_up(scores[4] + scores[3] / 2),
+                ]
+            }
         }
     }
 }
@@ -33,6 +59,7 @@ impl std::fmt::Display for CalculatorErrors {
 ///     1. Han
 ///     2. Fu
 /// 5. Is open (Does the hand contain any open melds)
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn get_hand_score(
     tiles: Vec<String>,
     win: String,
@@ -85,7 +112,7 @@ pub fn get_hand_score(
             hand.calculate_fu(tsumo)
         }
     };
-    let han_and_fu = vec![yaku.0 + dora, calculate_total_fu_value(&fu)];
+    let han_and_fu = vec![yaku.0.saturating_add(dora), calculate_total_fu_value(&fu)];
 
     let mut has_yakuman = false;
     for y in &yaku.1 {
@@ -104,7 +131,54 @@ pub fn get_hand_score(
     Ok((scores, yaku.1, fu, han_and_fu, hand.is_open()))
 }
 
+/// Get the score breakdown of the hand in sanma (three-player mahjong).
+///
+/// Each North tile set aside (kita) counts as an additional dora.
+/// The output is the same as [`get_hand_score()`], with the payment amounts following the tsumo payment `rule`.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn get_sanma_hand_score(
+    tiles: Vec<String>,
+    win: String,
+    dora: u16,
+    kita: u16,
+    seat: String,
+    prev: String,
+    tsumo: bool,
+    riichi: bool,
+    doubleriichi: bool,
+    ippatsu: bool,
+    haitei: bool,
+    rinshan: bool,
+    chankan: bool,
+    tenhou: bool,
+    honba: u16,
+    rule: SanmaTsumo,
+) -> Result<(Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool), HandErr> {
+    Hand::new(tiles.clone(), win.clone(), seat.clone(), prev.clone())?.validate_sanma()?;
+
+    let mut result = get_hand_score(
+        tiles,
+        win,
+        dora.saturating_add(kita),
+        seat,
+        prev,
+        tsumo,
+        riichi,
+        doubleriichi,
+        ippatsu,
+        haitei,
+        rinshan,
+        chankan,
+        tenhou,
+        0,
+    )?;
+    result.0 = add_honba(rule.payments(&result.0), honba);
+
+    Ok(result)
+}
+
 /// Get the yaku score and list of yaku given a hand and some round context.
+#[allow(clippy::too_many_arguments)]
 pub fn get_yaku_han(
     hand: &Hand,
     riichi: bool,
@@ -208,7 +282,7 @@ pub fn calculate_yakuman(yaku: &Vec<Yaku>) -> Result<Vec<u32>, HandErr> {
         return Err(HandErr::NoYaku);
     }
 
-    let basepoints: u32 = (8000 * total).into();
+    let basepoints: u32 = 8000 * u32::from(total);
     let scores = vec![
         basepoints * 6,
         basepoints * 2,
@@ -224,8 +298,8 @@ pub fn calculate_yakuman(yaku: &Vec<Yaku>) -> Result<Vec<u32>, HandErr> {
 ///
 /// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the returned `Vec`.
 pub fn calculate(args: &[u16], honba: u16) -> Result<Vec<u32>, HandErr> {
-    let han = args[0];
-    let fu = args[1];
+    let han = args.first().copied().unwrap_or(0);
+    let fu = args.get(1).copied().unwrap_or(0);
 
     if han == 0 {
         return Err(HandErr::NoHan);
@@ -237,17 +311,16 @@ pub fn calculate(args: &[u16], honba: u16) -> Result<Vec<u32>, HandErr> {
 
     let k = LimitHands::get_limit_hand(han, fu);
     if let Some(limithand) = k {
-        let mut scores = limithand.get_score();
-        scores[0] += honba * 300;
-        scores[1] += honba * 100;
-        scores[2] += honba * 300;
-        scores[3] += honba * 100;
-        scores[4] += honba * 100;
-
-        return Ok(scores.iter().map(|&score| score.into()).collect());
+        let scores = limithand.get_score();
+        return Ok(add_honba(
+            scores.iter().map(|&score| score.into()).collect(),
+            honba,
+        ));
     }
 
-    let basic_points = fu * 2u16.pow((han + 2).into());
+    // Widened so that large fu or honba counts cannot overflow.
+    let basic_points = u32::from(fu) * 2u32.pow((han + 2).into());
+    let honba = u32::from(honba);
 
     let dealer_ron = (((basic_points * 6 + honba * 300) as f64 / 100.0).ceil() * 100.0) as u32;
     let dealer_tsumo = (((basic_points * 2 + honba * 100) as f64 / 100.0).ceil() * 100.0) as u32;
@@ -267,3 +340,101 @@ pub fn calculate(args: &[u16], honba: u16) -> Result<Vec<u32>, HandErr> {
 
     Ok(scores)
 }
+
+/// Calculate the sanma (three-player mahjong) payment amounts from the han, fu, and number of honba (repeat counters).
+///
+/// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the returned `Vec`.
+pub fn calculate_sanma(args: &[u16], honba: u16, rule: SanmaTsumo) -> Result<Vec<u32>, HandErr> {
+    let scores = calculate(args, 0)?;
+    Ok(add_honba(rule.payments(&scores), honba))
+}
+
+/// Add the honba (repeat counter) payments to the payment amounts.
+fn add_honba(mut scores: Vec<u32>, honba: u16) -> Vec<u32> {
+    let honba = u32::from(honba);
+    scores[0] += honba * 300;
+    scores[1] += honba * 100;
+    scores[2] += honba * 300;
+    scores[3] += honba * 100;
+    scores[4] += honba * 100;
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{calculate, get_hand_score};
+    use crate::hand::Hand;
+    use crate::tile_group::{GroupType, TileGroup};
+
+    /// Generate a tile group in the hand notation, with `sizes` tiles when not a sequence.
+    fn tile_group(sizes: std::ops::RangeInclusive<usize>) -> impl Strategy<Value = String> {
+        let group = prop_oneof![
+            (1..=9u8, "[mps]", sizes.clone()).prop_map(|(v, suit, n)| format!(
+                "{}{}",
+                v.to_string().repeat(n),
+                suit
+            )),
+            (1..=7u8, "[mps]").prop_map(|(v, suit)| format!("{}{}{}{}", v, v + 1, v + 2, suit)),
+            ("[ESWN]", sizes.clone()).prop_map(|(v, n)| format!("{}w", v.repeat(n))),
+            ("[rgw]", sizes).prop_map(|(v, n)| format!("{}d", v.repeat(n))),
+        ];
+        (group, any::<bool>()).prop_map(|(group, open)| if open { group + "o" } else { group })
+    }
+
+    /// Generate hands of 4 groups and a pair, with a winning tile from the last group, so that hands reach the yaku and fu checks.
+    fn hand() -> impl Strategy<Value = (Vec<String>, String)> {
+        (
+            prop::collection::vec(tile_group(3..=4), 4),
+            tile_group(2..=2),
+            0..=4usize,
+            0..3usize,
+        )
+            .prop_map(|(mut groups, pair, pair_index, win_index)| {
+                groups.insert(pair_index, pair);
+                let last: Vec<char> = groups[4].chars().filter(|&c| c != 'o').collect();
+                let (suit, values) = last.split_last().unwrap();
+                let win = format!("{}{}", values[win_index % values.len()], suit);
+                (groups, win)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_arbitrary_group(group in ".*") {
+            let _ = TileGroup::new(group.clone());
+            let _ = GroupType::group_type_from_string(group);
+        }
+
+        #[test]
+        fn parse_arbitrary_hand(
+            tiles in prop::collection::vec(".{0,6}", 0..15),
+            win in ".{0,3}",
+            seat in ".{0,3}",
+            prev in ".{0,3}",
+        ) {
+            let _ = Hand::new(tiles, win, prev, seat);
+        }
+
+        #[test]
+        fn score_hand_like_input(
+            (tiles, win) in hand(),
+            seat in "[ESWN]w|.{0,2}",
+            prev in "[ESWN]w|.{0,2}",
+            dora: u16,
+            flags in prop::array::uniform8(any::<bool>()),
+            honba: u16,
+        ) {
+            let _ = get_hand_score(
+                tiles, win, dora, seat, prev, flags[0], flags[1], flags[2], flags[3], flags[4],
+                flags[5], flags[6], flags[7], honba,
+            );
+        }
+
+        #[test]
+        fn calculate_arbitrary_han_and_fu(args in prop::collection::vec(any::<u16>(), 0..3), honba: u16) {
+            let _ = calculate(&args, honba);
+        }
+    }
+}
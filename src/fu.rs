@@ -61,7 +61,7 @@ impl Fu {
 
 /// Sum up all of the fu, rounding to the nearest 10.
 pub fn calculate_total_fu_value(fu: &[Fu]) -> u16 {
-    ((fu.iter().map(|f| f.value()).sum::<u16>() + 9) / 10) * 10
+    fu.iter().map(|f| f.value()).sum::<u16>().div_ceil(10) * 10
 }
 
 #[cfg(test)]
@@ -80,7 +80,7 @@ mod tests {
                 "789m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -110,7 +110,7 @@ mod tests {
                 "789m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -139,7 +139,7 @@ mod tests {
                 "456s".to_string(),
             ],
             "6s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -0,0 +1,466 @@
+//! State of a whole game, moving from round to round as each one is settled.
+
+use crate::hand::error::HandErr;
+use crate::settlement::{
+    settle, settle_draw, settle_multi_ron, validate_seat, MultiRonRules, Settlement, Win,
+    PLAYER_COUNT,
+};
+use crate::standings::placements;
+
+const WIND_NAMES: [&str; 4] = ["East", "South", "West", "North"];
+
+/// Winds in the hand notation, starting from East.
+const WIND_TILES: [&str; 4] = ["Ew", "Sw", "Ww", "Nw"];
+
+/// How many winds are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameLength {
+    /// East round only (tonpuusen).
+    East,
+    /// East and South rounds (hanchan).
+    South,
+}
+
+impl GameLength {
+    /// Index of the last wind played, without any extension.
+    fn last_wind(&self) -> usize {
+        match self {
+            Self::East => 0,
+            Self::South => 1,
+        }
+    }
+}
+
+/// Ruleset options for playing a game through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameRules {
+    pub length: GameLength,
+    /// Points each player starts with.
+    pub starting_points: i32,
+    /// Points a player needs for the game to end after the last round.
+    ///
+    /// If nobody has them, the game goes on into the next wind until somebody does (sudden death).
+    /// `None` always ends the game after the last round.
+    pub target: Option<i32>,
+    /// The game ends as soon as a player falls below zero points (tobi).
+    pub busting: bool,
+    /// The dealer ends the game by winning the last round in first place (agari-yame).
+    pub agari_yame: bool,
+    /// The dealer ends the game by being tenpai at an exhaustive draw of the last round in first place (tenpai-yame).
+    pub tenpai_yame: bool,
+    pub multi_ron: MultiRonRules,
+}
+
+impl Default for GameRules {
+    /// Tenhou rules: a hanchan from 25000 points with a target of 30000, busting and agari-yame.
+    fn default() -> Self {
+        Self {
+            length: GameLength::South,
+            starting_points: 25000,
+            target: Some(30000),
+            busting: true,
+            agari_yame: true,
+            tenpai_yame: false,
+            multi_ron: MultiRonRules::default(),
+        }
+    }
+}
+
+/// A round of the game (ex: South 2 with 1 honba).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    /// Index of the round wind, starting from East.
+    pub wind: usize,
+    /// Index of the round in its wind, which is also the seat of the dealer.
+    pub number: usize,
+    pub honba: u16,
+}
+
+impl Round {
+    /// Seat index (0-3) of the dealer.
+    pub fn dealer(&self) -> usize {
+        self.number
+    }
+
+    /// Round (prevalent) wind in the hand notation (ex: `Sw`).
+    pub fn wind_tile(&self) -> &'static str {
+        WIND_TILES[self.wind % 4]
+    }
+
+    /// Seat wind of the seat in the hand notation (ex: `Sw`).
+    pub fn seat_wind(&self, seat: usize) -> Result<&'static str, HandErr> {
+        validate_seat(seat)?;
+        Ok(WIND_TILES[(seat + PLAYER_COUNT - self.dealer()) % PLAYER_COUNT])
+    }
+}
+
+impl std::fmt::Display for Round {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}-{}",
+            WIND_NAMES[self.wind % 4],
+            self.number + 1,
+            self.honba
+        )
+    }
+}
+
+/// How a round ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundEnd {
+    /// One or more players won. Several wins must all be ron off the same discard.
+    Win(Vec<Win>),
+    /// Exhaustive draw (ryuukyoku), with the seats that are tenpai.
+    Draw { tenpai: [bool; PLAYER_COUNT] },
+    /// Abortive draw (ex: nine terminals, four riichi).
+    AbortiveDraw,
+}
+
+/// Scores and round of a game in progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    pub rules: GameRules,
+    pub scores: [i32; PLAYER_COUNT],
+    pub round: Round,
+    /// Riichi sticks left on the table.
+    pub riichi_sticks: u16,
+    pub finished: bool,
+}
+
+impl Game {
+    /// Start a game at East 1, with seat 0 as the first dealer.
+    pub fn new(rules: GameRules) -> Self {
+        Self {
+            rules,
+            scores: [rules.starting_points; PLAYER_COUNT],
+            round: Round {
+                wind: 0,
+                number: 0,
+                honba: 0,
+            },
+            riichi_sticks: 0,
+            finished: false,
+        }
+    }
+
+    /// Settle the current round and move on to the next one, or end the game.
+    ///
+    /// Every seat in `riichi` declared riichi this round. Winning hands are settled with [`settle()`] or [`settle_multi_ron()`],
+    /// and draws with [`settle_draw()`]. The dealer repeats (renchan) on a win they are paid for or tenpai, and on an abortive draw.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::calc::calculate;
+    /// use mahc::game::{Game, GameRules, RoundEnd};
+    /// use mahc::settlement::Win;
+    ///
+    /// let mut game = Game::new(GameRules::default());
+    /// let win = Win {
+    ///     winner: 2,
+    ///     loser: Some(0),
+    ///     payment: calculate(&[3, 30], 0).unwrap(),
+    ///     liable: None,
+    /// };
+    /// game.end_round(&RoundEnd::Win(vec![win]), [false; 4]).unwrap();
+    ///
+    /// assert_eq!(game.scores, [21100, 25000, 28900, 25000]);
+    /// assert_eq!(game.round.to_string(), "East 2-0");
+    /// ```
+    pub fn end_round(
+        &mut self,
+        end: &RoundEnd,
+        riichi: [bool; PLAYER_COUNT],
+    ) -> Result<Settlement, HandErr> {
+        if self.finished {
+            return Err(HandErr::GameOver);
+        }
+        let dealer = self.round.dealer();
+        let honba = self.round.honba;
+
+        let (settlement, dealer_repeats, draw, yame) = match end {
+            RoundEnd::Win(wins) => {
+                let settlement = match wins.as_slice() {
+                    [win] => settle(self.scores, dealer, win, honba, self.riichi_sticks, riichi)?,
+                    wins => settle_multi_ron(
+                        self.scores,
+                        dealer,
+                        wins,
+                        honba,
+                        self.riichi_sticks,
+                        riichi,
+                        self.rules.multi_ron,
+                    )?,
+                };
+                // A triple ron may be an abortive draw, rather than a win.
+                let aborted = self.rules.multi_ron.is_abortive(wins.len());
+                let dealer_won = self
+                    .rules
+                    .multi_ron
+                    .paid_wins(wins)?
+                    .iter()
+                    .any(|win| win.winner == dealer);
+                (
+                    settlement,
+                    aborted || dealer_won,
+                    aborted,
+                    dealer_won && self.rules.agari_yame,
+                )
+            }
+            RoundEnd::Draw { tenpai } => (
+                settle_draw(self.scores, *tenpai, self.riichi_sticks, riichi),
+                tenpai[dealer],
+                true,
+                tenpai[dealer] && self.rules.tenpai_yame,
+            ),
+            RoundEnd::AbortiveDraw => (
+                settle_draw(
+                    self.scores,
+                    [false; PLAYER_COUNT],
+                    self.riichi_sticks,
+                    riichi,
+                ),
+                true,
+                true,
+                false,
+            ),
+        };
+
+        self.scores = settlement.scores;
+        self.riichi_sticks = settlement.riichi_sticks;
+        self.finished = self.is_over(dealer_repeats, yame);
+        if !self.finished {
+            self.next_round(dealer_repeats, draw);
+        }
+        Ok(settlement)
+    }
+
+    /// Check if the game ends after the current round was settled.
+    fn is_over(&self, dealer_repeats: bool, yame: bool) -> bool {
+        if self.rules.busting && self.scores.iter().any(|&score| score < 0) {
+            return true;
+        }
+
+        let last_wind = self.rules.length.last_wind();
+        let target_reached = |target: i32| self.scores.iter().any(|&score| score >= target);
+        let extension = self.round.wind > last_wind;
+        if extension && self.rules.target.is_some_and(target_reached) {
+            return true;
+        }
+        if self.round.wind < last_wind || self.round.number < PLAYER_COUNT - 1 {
+            return false;
+        }
+
+        if !dealer_repeats {
+            return extension || self.rules.target.is_none_or(target_reached);
+        }
+        let dealer = self.round.dealer();
+        yame && self
+            .rules
+            .target
+            .is_none_or(|target| self.scores[dealer] >= target)
+            && placements(&self.scores)[dealer] == 0
+    }
+
+    /// Move on to the next round, keeping the dealer if they repeat.
+    ///
+    /// The honba count goes up on a dealer repeat or a draw, and is reset otherwise.
+    fn next_round(&mut self, dealer_repeats: bool, draw: bool) {
+        let honba = if dealer_repeats || draw {
+            self.round.honba.saturating_add(1)
+        } else {
+            0
+        };
+        if !dealer_repeats {
+            self.round.number += 1;
+            if self.round.number == PLAYER_COUNT {
+                self.round.number = 0;
+                self.round.wind += 1;
+            }
+        }
+        self.round.honba = honba;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calc::calculate;
+    use crate::settlement::MultiRonRule;
+
+    fn ron(winner: usize, loser: usize, han: u16) -> RoundEnd {
+        RoundEnd::Win(vec![Win {
+            winner,
+            loser: Some(loser),
+            payment: calculate(&[han, 30], 0).unwrap(),
+            liable: None,
+        }])
+    }
+
+    /// A game at the given round, with the given scores.
+    fn game_at(wind: usize, number: usize, scores: [i32; 4]) -> Game {
+        let mut game = Game::new(GameRules::default());
+        game.round = Round {
+            wind,
+            number,
+            honba: 0,
+        };
+        game.scores = scores;
+        game
+    }
+
+    #[test]
+    fn dealer_repeats_on_win() {
+        let mut game = Game::new(GameRules::default());
+        game.end_round(&ron(0, 1, 2), [false, true, false, false])
+            .unwrap();
+        assert_eq!(game.scores, [28900, 21100, 25000, 25000]);
+        assert_eq!(game.round.to_string(), "East 1-1");
+
+        // the honba is paid and reset when the deal moves on
+        game.end_round(&ron(1, 0, 1), [false; 4]).unwrap();
+        assert_eq!(game.scores, [27600, 22400, 25000, 25000]);
+        assert_eq!(game.round.to_string(), "East 2-0");
+        assert_eq!(game.round.seat_wind(1), Ok("Ew"));
+        assert_eq!(game.round.seat_wind(0), Ok("Nw"));
+    }
+
+    #[test]
+    fn draws() {
+        let mut game = Game::new(GameRules::default());
+        game.end_round(
+            &RoundEnd::Draw {
+                tenpai: [false, true, false, false],
+            },
+            [false, true, false, false],
+        )
+        .unwrap();
+        assert_eq!(game.scores, [24000, 27000, 24000, 24000]);
+        assert_eq!(
+            (game.round.to_string(), game.riichi_sticks),
+            ("East 2-1".to_string(), 1)
+        );
+
+        game.end_round(&RoundEnd::AbortiveDraw, [true, false, false, false])
+            .unwrap();
+        assert_eq!(
+            (game.round.to_string(), game.riichi_sticks),
+            ("East 2-2".to_string(), 2)
+        );
+
+        // the winner takes the sticks left on the table
+        game.end_round(&ron(3, 2, 1), [false; 4]).unwrap();
+        assert_eq!(game.scores, [23000, 27000, 22400, 27600]);
+        assert_eq!(
+            (game.round.to_string(), game.riichi_sticks),
+            ("East 3-0".to_string(), 0)
+        );
+
+        game.round.honba = u16::MAX;
+        game.end_round(&RoundEnd::AbortiveDraw, [false; 4]).unwrap();
+        assert_eq!(game.round.honba, u16::MAX);
+    }
+
+    #[test]
+    fn double_ron() {
+        let wins = RoundEnd::Win(vec![
+            Win {
+                winner: 3,
+                loser: Some(2),
+                payment: calculate(&[1, 30], 0).unwrap(),
+                liable: None,
+            },
+            Win {
+                winner: 0,
+                loser: Some(2),
+                payment: calculate(&[2, 30], 0).unwrap(),
+                liable: None,
+            },
+        ]);
+        let mut game = Game::new(GameRules::default());
+        game.end_round(&wins, [false; 4]).unwrap();
+        assert_eq!(game.scores, [27900, 25000, 21100, 26000]);
+        assert_eq!(game.round.to_string(), "East 1-1");
+
+        // with the head bump, only seat 3 is paid so the dealer does not repeat
+        let mut game = Game::new(GameRules::default());
+        game.rules.multi_ron.rule = MultiRonRule::Atamahane;
+        game.end_round(&wins, [false; 4]).unwrap();
+        assert_eq!(game.scores, [25000, 25000, 24000, 26000]);
+        assert_eq!(game.round.to_string(), "East 2-0");
+
+        // nor does the dealer repeat all-last, so the game goes on into West
+        let mut game = game_at(1, 3, [25000, 25000, 20000, 29000]);
+        game.rules.multi_ron.rule = MultiRonRule::Atamahane;
+        let wins = RoundEnd::Win(vec![
+            Win {
+                winner: 3,
+                loser: Some(1),
+                payment: calculate(&[1, 30], 0).unwrap(),
+                liable: None,
+            },
+            Win {
+                winner: 2,
+                loser: Some(1),
+                payment: calculate(&[1, 30], 0).unwrap(),
+                liable: None,
+            },
+        ]);
+        game.end_round(&wins, [false; 4]).unwrap();
+        assert_eq!(game.scores, [25000, 24000, 21000, 29000]);
+        assert!(!game.finished);
+        assert_eq!(game.round.to_string(), "West 1-0");
+    }
+
+    #[test]
+    fn game_ends_after_last_round() {
+        let mut game = game_at(1, 3, [31000, 25000, 24000, 20000]);
+        game.end_round(&ron(0, 1, 1), [false; 4]).unwrap();
+        assert!(game.finished);
+        assert_eq!(
+            game.end_round(&RoundEnd::AbortiveDraw, [false; 4]),
+            Err(HandErr::GameOver)
+        );
+
+        // nobody reached 30000 points, so the game goes on into West
+        let mut game = game_at(1, 3, [26000, 25000, 25000, 24000]);
+        game.end_round(&ron(0, 1, 1), [false; 4]).unwrap();
+        assert!(!game.finished);
+        assert_eq!(game.round.to_string(), "West 1-0");
+        game.end_round(&ron(2, 1, 5), [false; 4]).unwrap();
+        assert!(game.finished);
+    }
+
+    #[test]
+    fn game_ends_on_busting() {
+        let mut game = game_at(0, 1, [25000, 25000, 1000, 49000]);
+        game.end_round(&ron(1, 2, 2), [false; 4]).unwrap();
+        assert!(game.finished);
+        assert_eq!(game.scores[2], -1900);
+
+        game.rules.busting = false;
+        game.finished = false;
+        game.end_round(&ron(1, 3, 1), [false; 4]).unwrap();
+        assert!(!game.finished);
+    }
+
+    #[test]
+    fn agari_yame() {
+        // the dealer wins the last round in first place
+        let mut game = game_at(1, 3, [25000, 25000, 20000, 29000]);
+        game.end_round(&ron(3, 2, 1), [false; 4]).unwrap();
+        assert!(game.finished);
+
+        let mut game = game_at(1, 3, [25000, 25000, 20000, 29000]);
+        game.rules.agari_yame = false;
+        game.end_round(&ron(3, 2, 1), [false; 4]).unwrap();
+        assert!(!game.finished);
+        assert_eq!(game.round.to_string(), "South 4-1");
+
+        // the dealer is not in first place
+        let mut game = game_at(1, 3, [40000, 20000, 10000, 30000]);
+        game.end_round(&ron(3, 2, 1), [false; 4]).unwrap();
+        assert!(!game.finished);
+    }
+}
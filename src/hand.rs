@@ -1,10 +1,12 @@
 pub mod error;
 
+use std::collections::HashMap;
+
 use crate::fu::Fu;
 use crate::suit::Suit;
-use crate::tile_group::{GroupType, TileGroup};
+use crate::tile_group::{GroupType, MeldKind, Source, TileGroup};
 use crate::TERMINAL_CHARS;
-use error::HandErr;
+use error::{HandErr, InputErr};
 
 #[derive(Debug)]
 pub struct Hand {
@@ -27,8 +29,8 @@ impl Hand {
         let mut ishandopen = false;
 
         // NOTE: Strings are complicated in Rust and needs evaluation about how to iterate over one. Because the string is expected to contain ASCII characters, `.chars()` should be okay.
-        for i in &tiles {
-            let tile = TileGroup::new(i.to_string())?;
+        for (index, i) in tiles.iter().enumerate() {
+            let tile = TileGroup::new(i.to_string()).map_err(|e| e.in_group(index))?;
             if tile.isopen {
                 ishandopen = true;
             }
@@ -51,59 +53,64 @@ impl Hand {
             && pair_count != 7
             && !(no_shape_count == 12 && pair_count == 1)
         {
-            return Err(HandErr::InvalidShape);
+            return Err(HandErr::InvalidShape(InputErr::whole(
+                &tiles.join(" "),
+                "4 groups and a pair, 7 pairs, or 13 orphans",
+                &format!(
+                    "{} groups, {} pairs and {} single tiles",
+                    full_shape_count, pair_count, no_shape_count
+                ),
+            )));
         }
 
         // AHAHAHAHAHAHAHAHAh (these are special cases for singular tiles)
-        let win_tile = TileGroup {
-            value: win.chars().nth(0).unwrap().to_string(),
-            suit: Suit::suit_from_string(win.chars().nth(1).unwrap().to_string())?,
-            isopen: false,
-            group_type: GroupType::None,
-            isterminal: TERMINAL_CHARS.contains(&win.chars().nth(0).unwrap()),
-        };
+        let win_tile = single_tile(&win)?;
 
         // check if last group contains the winning tile
         // FUCK handling kokuushi
         if tiles.len() != 13 {
-            let last_group = tile_groups.last().unwrap();
+            let Some(last_group) = tile_groups.last() else {
+                return Err(HandErr::NoHandTiles);
+            };
+            let last_index = tiles.len() - 1;
+            let missing_win_tile = || {
+                HandErr::InvalidShape(InputErr::whole(
+                    &tiles[last_index],
+                    &format!("the last group to contain the winning tile `{}`", win),
+                    &format!("`{}`", tiles[last_index]),
+                ))
+                .in_group(last_index)
+            };
             match last_group.group_type {
                 GroupType::Sequence => {
                     if win_tile.suit != last_group.suit {
-                        return Err(HandErr::InvalidShape);
+                        return Err(missing_win_tile());
                     }
 
-                    let win_int = win_tile.parse_u8().unwrap();
-                    let last_int = last_group.parse_u8().unwrap();
-
-                    if win_int != last_int && win_int != last_int + 1 && win_int != last_int + 2 {
-                        return Err(HandErr::InvalidShape);
+                    match (win_tile.parse_u8(), last_group.parse_u8()) {
+                        (Ok(win_int), Ok(last_int))
+                            if win_int >= last_int && win_int <= last_int + 2 => {}
+                        _ => return Err(missing_win_tile()),
                     }
                 }
                 GroupType::Triplet | GroupType::Pair => {
                     if last_group.value != win_tile.value || last_group.suit != win_tile.suit {
-                        return Err(HandErr::InvalidShape);
+                        return Err(missing_win_tile());
                     }
                 }
-                GroupType::Kan | GroupType::None => return Err(HandErr::InvalidShape),
+                GroupType::Kan | GroupType::None => {
+                    return Err(HandErr::InvalidShape(InputErr::whole(
+                        &tiles[last_index],
+                        "the last group to be a sequence, triplet or pair",
+                        &format!("`{}`", tiles[last_index]),
+                    ))
+                    .in_group(last_index))
+                }
             }
         }
 
-        let seat_tile = TileGroup {
-            value: seat.chars().nth(0).unwrap().to_string(),
-            suit: Suit::suit_from_string(seat.chars().nth(1).unwrap().to_string())?,
-            isopen: false,
-            group_type: GroupType::None,
-            isterminal: TERMINAL_CHARS.contains(&seat.chars().nth(0).unwrap()),
-        };
-
-        let prev_tile = TileGroup {
-            value: prev.chars().nth(0).unwrap().to_string(),
-            suit: Suit::suit_from_string(prev.chars().nth(1).unwrap().to_string())?,
-            isopen: false,
-            group_type: GroupType::None,
-            isterminal: TERMINAL_CHARS.contains(&prev.chars().nth(0).unwrap()),
-        };
+        let seat_tile = single_tile(&seat)?;
+        let prev_tile = single_tile(&prev)?;
 
         let hand = Self {
             groups: tile_groups,
@@ -112,10 +119,61 @@ impl Hand {
             prev_tile,
             isopen: ishandopen,
         };
+        hand.validate_tile_counts(&[])?;
 
         Ok(hand)
     }
 
+    /// Check that no tile appears more than four times across the hand and the dora `indicators`.
+    ///
+    /// The winning tile is counted as part of the group it completes.
+    pub fn validate_tile_counts(&self, indicators: &[String]) -> Result<(), HandErr> {
+        let mut indicator_groups: Vec<TileGroup> = Vec::new();
+        for indicator in indicators {
+            let group = TileGroup::new(indicator.to_string())?;
+            if group.group_type != GroupType::None {
+                return Err(HandErr::InvalidGroup(InputErr::whole(
+                    indicator,
+                    "a single dora indicator tile",
+                    &format!("`{}`", indicator),
+                )));
+            }
+            indicator_groups.push(group);
+        }
+
+        let mut counts: HashMap<String, u8> = HashMap::new();
+        for tile in self
+            .groups
+            .iter()
+            .chain(indicator_groups.iter())
+            .flat_map(|group| group.tiles())
+        {
+            let count = counts.entry(tile.clone()).or_insert(0);
+            *count += 1;
+            if *count > 4 {
+                return Err(HandErr::TooManyCopies { tile });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the hand can be played in sanma (three-player mahjong).
+    ///
+    /// Along with the tiles of every group, neither the seat wind nor the prevalent wind can be North.
+    pub fn validate_sanma(&self) -> Result<(), HandErr> {
+        for group in &self.groups {
+            group.validate_sanma()?;
+        }
+        self.win_tile.validate_sanma()?;
+
+        if self.seat_tile.value == "N" || self.prev_tile.value == "N" {
+            return Err(HandErr::SanmaNorthWind);
+        }
+
+        Ok(())
+    }
+
     /// Calculate the fu types in the hand.
     pub fn calculate_fu(&self, tsumo: bool) -> Vec<Fu> {
         let mut fu_types: Vec<Fu> = vec![];
@@ -282,6 +340,33 @@ impl Hand {
         self.isopen
     }
 
+    /// Render the hand as Unicode mahjong tiles, with the concealed groups together and each kan or open meld after them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::Hand;
+    ///
+    /// let tiles = ["rrrdo", "1111p", "234m", "678s", "55s"].map(String::from).to_vec();
+    /// let hand = Hand::new(tiles, "5s".to_string(), "Ew".to_string(), "Ew".to_string()).unwrap();
+    ///
+    /// assert_eq!(hand.to_unicode(), "🀈🀉🀊🀕🀖🀗🀔🀔 🀄🀄🀄 🀫🀙🀙🀫");
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        let (melds, concealed): (Vec<&TileGroup>, Vec<&TileGroup>) = self
+            .groups
+            .iter()
+            .partition(|group| group.isopen || group.group_type == GroupType::Kan);
+        let mut out: String = concealed.iter().map(|group| group.to_unicode()).collect();
+        for meld in melds {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(&meld.to_unicode());
+        }
+        out
+    }
+
     //yaku validation
 
     /// Check if the hand only contains simple tiles -- no terminal or honor tiles.
@@ -826,6 +911,61 @@ impl Hand {
             == 4
     }
 
+    /// Get the player liable (pao) for feeding the meld that completed a yakuman, if any.
+    ///
+    /// Melds are expected in the order they were called, so the last dragon or wind meld is the one that completed the yakuman.
+    /// Daisangen and daisuushii always carry pao; suukantsu only does if `suukantsu` is set by the ruleset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::Hand;
+    /// use mahc::tile_group::Source;
+    ///
+    /// let tiles = ["rrrdo", "gggdoR", "wwwdoA", "234m", "55s"].map(String::from).to_vec();
+    /// let hand = Hand::new(tiles, "5s".to_string(), "Ew".to_string(), "Ew".to_string()).unwrap();
+    ///
+    /// assert_eq!(hand.liable_source(false), Some(Source::Across));
+    /// ```
+    pub fn liable_source(&self, suukantsu: bool) -> Option<Source> {
+        let last_meld_source = |filter: &dyn Fn(&TileGroup) -> bool| {
+            self.groups
+                .iter()
+                .rfind(|group| {
+                    matches!(group.group_type, GroupType::Triplet | GroupType::Kan) && filter(group)
+                })?
+                .meld
+                .as_ref()?
+                .from
+        };
+
+        if self.is_daisangen() {
+            if let Some(source) = last_meld_source(&|group| group.suit == Suit::Dragon) {
+                return Some(source);
+            }
+        }
+        if self.is_daisuushii() {
+            if let Some(source) = last_meld_source(&|group| group.suit == Suit::Wind) {
+                return Some(source);
+            }
+        }
+        if suukantsu && self.is_suukantsu() {
+            return last_meld_source(&|group| group.group_type == GroupType::Kan);
+        }
+        None
+    }
+
+    /// Check that a kan of the `kind` can be robbed by chankan to complete this hand.
+    ///
+    /// Only an added kan can be robbed, except for a closed kan when the hand is kokushi musou (thirteen orphans).
+    pub fn validate_chankan(&self, kind: MeldKind) -> Result<(), HandErr> {
+        match kind {
+            MeldKind::Shouminkan => Ok(()),
+            MeldKind::Ankan if self.is_kokushi() => Ok(()),
+            _ => Err(HandErr::ChankanInvalidKan),
+        }
+    }
+
     /// Check if the hand has one of each type of terminal and honor tile and one additional terminal or honor tile.
     pub fn is_kokushi(&self) -> bool {
         if self.singles().len() != 12 || self.pairs().len() != 1 {
@@ -888,10 +1028,28 @@ impl Hand {
     }
 }
 
+/// Parse a single tile, such as the winning tile or a wind.
+fn single_tile(tile: &str) -> Result<TileGroup, HandErr> {
+    let group = TileGroup::new(tile.to_string())?;
+    if group.group_type != GroupType::None || group.isopen {
+        return Err(HandErr::InvalidGroup(InputErr::whole(
+            tile,
+            "a single tile (ex: 5p, Ew, rd)",
+            &format!("`{}`", tile),
+        )));
+    }
+
+    Ok(TileGroup {
+        isterminal: TERMINAL_CHARS.contains(&group.value.chars().next().unwrap_or_default()),
+        ..group
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::Hand;
-    use crate::hand::error::HandErr;
+    use crate::hand::error::{ErrorCategory, HandErr, InputErr};
+    use crate::tile_group::{MeldKind, Source};
 
     #[test]
     fn yaku_kokushi() {
@@ -912,7 +1070,7 @@ mod tests {
                 "wwd".to_string(),
             ],
             "wd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -934,7 +1092,7 @@ mod tests {
                 "wwd".to_string(),
             ],
             "wd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -956,7 +1114,7 @@ mod tests {
                 "wwd".to_string(),
             ],
             "wd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -979,7 +1137,7 @@ mod tests {
                 "rd".to_string(),
             ],
             "rd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1002,7 +1160,7 @@ mod tests {
                 "wwd".to_string(),
             ],
             "wd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1020,7 +1178,7 @@ mod tests {
                 "99s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1034,7 +1192,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1052,7 +1210,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1066,7 +1224,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1084,7 +1242,7 @@ mod tests {
                 "99s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1098,7 +1256,7 @@ mod tests {
                 "99s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1118,7 +1276,7 @@ mod tests {
                 "EEw".to_string(),
             ],
             "Ew".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1132,7 +1290,7 @@ mod tests {
                 "EEEw".to_string(),
             ],
             "Ew".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1150,7 +1308,7 @@ mod tests {
                 "EEEw".to_string(),
             ],
             "Ew".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1164,7 +1322,7 @@ mod tests {
                 "EEEw".to_string(),
             ],
             "Ew".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1182,7 +1340,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1196,7 +1354,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1210,7 +1368,7 @@ mod tests {
                 "99s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1224,7 +1382,7 @@ mod tests {
                 "55s".to_string(),
             ],
             "5s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1238,7 +1396,7 @@ mod tests {
                 "999s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1256,7 +1414,7 @@ mod tests {
                 "888s".to_string(),
             ],
             "8s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1270,7 +1428,7 @@ mod tests {
                 "888s".to_string(),
             ],
             "8s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1284,7 +1442,7 @@ mod tests {
                 "888s".to_string(),
             ],
             "8s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1299,7 +1457,7 @@ mod tests {
                 "99s".to_string(),
             ],
             "9s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1310,14 +1468,14 @@ mod tests {
     fn yaku_chinroutou() {
         let out = Hand::new(
             vec![
-                "111so".to_string(),
+                "999mo".to_string(),
                 "1111m".to_string(),
                 "999s".to_string(),
                 "999p".to_string(),
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1331,21 +1489,21 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
         assert!(!out.is_chinroutou());
         let out = Hand::new(
             vec![
-                "111s".to_string(),
+                "999mo".to_string(),
                 "1111m".to_string(),
                 "789s".to_string(),
                 "999p".to_string(),
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1363,7 +1521,7 @@ mod tests {
                 "11m".to_string(),
             ],
             "1m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1377,7 +1535,7 @@ mod tests {
                 "111s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1395,7 +1553,7 @@ mod tests {
                 "11m".to_string(),
             ],
             "1m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1410,7 +1568,7 @@ mod tests {
                 "111s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1424,7 +1582,7 @@ mod tests {
                 "111s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1442,7 +1600,7 @@ mod tests {
                 "567p".to_string(),
             ],
             "6p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1456,7 +1614,7 @@ mod tests {
                 "567p".to_string(),
             ],
             "6p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1471,7 +1629,7 @@ mod tests {
                 "567p".to_string(),
             ],
             "6p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1489,7 +1647,7 @@ mod tests {
                 "567p".to_string(),
             ],
             "6p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1503,7 +1661,7 @@ mod tests {
                 "567p".to_string(),
             ],
             "6p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1521,7 +1679,7 @@ mod tests {
                 "456m".to_string(),
             ],
             "6m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1535,7 +1693,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1549,7 +1707,7 @@ mod tests {
                 "333s".to_string(),
             ],
             "3s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1567,7 +1725,7 @@ mod tests {
                 "456m".to_string(),
             ],
             "6m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1582,7 +1740,7 @@ mod tests {
                 "456m".to_string(),
             ],
             "5m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1596,7 +1754,7 @@ mod tests {
                 "456m".to_string(),
             ],
             "5m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1610,7 +1768,7 @@ mod tests {
                 "rrrd".to_string(),
             ],
             "rd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1630,7 +1788,7 @@ mod tests {
                 "77p".to_string(),
             ],
             "7p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1647,7 +1805,7 @@ mod tests {
                 "77p".to_string(),
             ],
             "7p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1662,7 +1820,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1682,7 +1840,7 @@ mod tests {
                 "77p".to_string(),
             ],
             "7p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1696,7 +1854,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1714,7 +1872,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1729,21 +1887,21 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
         assert!(!out.is_chantaiyao());
         let out = Hand::new(
             vec![
-                "111s".to_string(),
+                "999s".to_string(),
                 "999p".to_string(),
                 "111p".to_string(),
                 "999m".to_string(),
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1761,7 +1919,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1776,7 +1934,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1790,7 +1948,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1804,7 +1962,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1822,7 +1980,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1836,7 +1994,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1854,7 +2012,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1869,7 +2027,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1884,7 +2042,7 @@ mod tests {
                 "99p".to_string(),
             ],
             "9p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1902,7 +2060,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1917,7 +2075,7 @@ mod tests {
                 "ggd".to_string(),
             ],
             "gd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1932,7 +2090,7 @@ mod tests {
                 "11p".to_string(),
             ],
             "1p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1950,7 +2108,7 @@ mod tests {
                 "gggd".to_string(),
             ],
             "gd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1958,13 +2116,13 @@ mod tests {
         let out = Hand::new(
             vec![
                 "123p".to_string(),
-                "222p".to_string(),
+                "222s".to_string(),
                 "wwwwd".to_string(),
                 "rrd".to_string(),
                 "234p".to_string(),
             ],
             "4p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1982,7 +2140,7 @@ mod tests {
                 "gggd".to_string(),
             ],
             "gd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -1997,7 +2155,7 @@ mod tests {
                 "gggd".to_string(),
             ],
             "gd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2012,7 +2170,7 @@ mod tests {
                 "33p".to_string(),
             ],
             "3p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2031,7 +2189,7 @@ mod tests {
                 "333p".to_string(),
             ],
             "3p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2046,7 +2204,7 @@ mod tests {
                 "333p".to_string(),
             ],
             "3p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2061,7 +2219,7 @@ mod tests {
                 "333p".to_string(),
             ],
             "3p".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2079,7 +2237,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2094,7 +2252,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2109,7 +2267,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2127,7 +2285,7 @@ mod tests {
                 "11s".to_string(),
             ],
             "1s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2142,7 +2300,7 @@ mod tests {
                 "gggd".to_string(),
             ],
             "gd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2157,7 +2315,7 @@ mod tests {
                 "456so".to_string(),
             ],
             "5s".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2175,7 +2333,7 @@ mod tests {
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2189,7 +2347,7 @@ mod tests {
                 "rrrd".to_string(),
             ],
             "rd".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2197,13 +2355,13 @@ mod tests {
         let out = Hand::new(
             vec![
                 "3333mo".to_string(),
-                "WWWm".to_string(),
+                "WWWw".to_string(),
                 "22s".to_string(),
-                "234m".to_string(),
+                "234p".to_string(),
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2213,11 +2371,11 @@ mod tests {
                 "3333mo".to_string(),
                 "222m".to_string(),
                 "22s".to_string(),
-                "234m".to_string(),
+                "234p".to_string(),
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2235,8 +2393,8 @@ mod tests {
                 "77m".to_string(),
             ],
             "7m".to_string(),
-            "es".to_string(),
-            "ww".to_string(),
+            "Ew".to_string(),
+            "Ww".to_string(),
         )
         .unwrap();
         //is open
@@ -2251,8 +2409,8 @@ mod tests {
                 "77m".to_string(),
             ],
             "7m".to_string(),
-            "es".to_string(),
-            "ww".to_string(),
+            "Ew".to_string(),
+            "Ww".to_string(),
         )
         .unwrap();
         //is open
@@ -2270,7 +2428,7 @@ mod tests {
                 "77m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2286,7 +2444,7 @@ mod tests {
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2302,7 +2460,7 @@ mod tests {
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2320,7 +2478,7 @@ mod tests {
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2334,7 +2492,7 @@ mod tests {
                 "678m".to_string(),
             ],
             "7m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
@@ -2348,13 +2506,301 @@ mod tests {
                 "345m".to_string(),
             ],
             "4m".to_string(),
-            "Es".to_string(),
+            "Ew".to_string(),
             "Ww".to_string(),
         )
         .unwrap();
         assert!(out.is_tanyao());
     }
 
+    #[test]
+    fn sanma_hand() {
+        let out = Hand::new(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "123p".to_string(),
+                "EEEwo".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_sanma(), Ok(()));
+        let out = Hand::new(
+            vec![
+                "111m".to_string(),
+                "555m".to_string(),
+                "123p".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_sanma(), Err(HandErr::SanmaInvalidTile));
+        let out = Hand::new(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "123po".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_sanma(), Err(HandErr::SanmaChii));
+        let out = Hand::new(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "123p".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Nw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_sanma(), Err(HandErr::SanmaNorthWind));
+    }
+
+    #[test]
+    fn called_melds() {
+        let out = Hand::new(
+            vec![
+                "345moL4".to_string(),
+                "EEEwoR".to_string(),
+                "5555po+A".to_string(),
+                "9999s".to_string(),
+                "11m".to_string(),
+            ],
+            "1m".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        )
+        .unwrap();
+        let melds: Vec<(MeldKind, Option<Source>, Option<String>)> = out
+            .groups
+            .iter()
+            .filter_map(|group| group.meld.clone())
+            .map(|meld| (meld.kind, meld.from, meld.claimed))
+            .collect();
+        assert_eq!(
+            melds,
+            vec![
+                (MeldKind::Chi, Some(Source::Left), Some("4m".to_string())),
+                (MeldKind::Pon, Some(Source::Right), None),
+                (MeldKind::Shouminkan, Some(Source::Across), None),
+                (MeldKind::Ankan, None, None),
+            ]
+        );
+        assert!(out.is_open());
+        assert_eq!(Source::Across.seat(3), 1);
+
+        for invalid in ["345moR", "345mo+", "345moL6", "555poLX", "11po5"] {
+            let out = Hand::new(
+                vec![
+                    invalid.to_string(),
+                    "EEEw".to_string(),
+                    "123s".to_string(),
+                    "999s".to_string(),
+                    "11m".to_string(),
+                ],
+                "1m".to_string(),
+                "Ew".to_string(),
+                "Ew".to_string(),
+            );
+            assert!(matches!(out, Err(HandErr::InvalidGroup(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn liable_source() {
+        let out = Hand::new(
+            vec![
+                "EEEwoL".to_string(),
+                "SSSw".to_string(),
+                "WWWwoA".to_string(),
+                "NNNNwoR".to_string(),
+                "11m".to_string(),
+            ],
+            "1m".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.liable_source(false), Some(Source::Right));
+
+        let out = Hand::new(
+            vec![
+                "rrrdoL".to_string(),
+                "gggd".to_string(),
+                "123s".to_string(),
+                "wwwd".to_string(),
+                "11m".to_string(),
+            ],
+            "1m".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.liable_source(false), None);
+    }
+
+    #[test]
+    fn chankan_kan_kind() {
+        let out = Hand::new(
+            vec![
+                "123m".to_string(),
+                "456p".to_string(),
+                "789s".to_string(),
+                "EEEw".to_string(),
+                "11p".to_string(),
+            ],
+            "1p".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_chankan(MeldKind::Shouminkan), Ok(()));
+        assert_eq!(
+            out.validate_chankan(MeldKind::Ankan),
+            Err(HandErr::ChankanInvalidKan)
+        );
+    }
+
+    #[test]
+    fn error_categories() {
+        let out = Hand::new(
+            vec![
+                "123m".to_string(),
+                "1z3m".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(out.category(), ErrorCategory::Parse);
+        assert_eq!(out.code(), "invalid_group");
+
+        let out = Hand::new(
+            vec!["123m".to_string(), "EEEw".to_string(), "55s".to_string()],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(out.category(), ErrorCategory::Shape);
+        assert_eq!(out.code(), "invalid_shape");
+
+        assert_eq!(HandErr::RonFromSelf.category(), ErrorCategory::Situation);
+        assert_eq!(HandErr::NoHan.category().code(), "score");
+    }
+
+    #[test]
+    fn too_many_copies() {
+        let out = Hand::new(
+            vec![
+                "1111m".to_string(),
+                "111m".to_string(),
+                "123p".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(
+            out.unwrap_err(),
+            HandErr::TooManyCopies {
+                tile: "1m".to_string()
+            }
+        );
+        let out = Hand::new(
+            vec![
+                "123m".to_string(),
+                "123m".to_string(),
+                "123m".to_string(),
+                "123m".to_string(),
+                "22m".to_string(),
+            ],
+            "2m".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(
+            out.unwrap_err(),
+            HandErr::TooManyCopies {
+                tile: "2m".to_string()
+            }
+        );
+        let out = Hand::new(
+            vec![
+                "EEEw".to_string(),
+                "123m".to_string(),
+                "456p".to_string(),
+                "789s".to_string(),
+                "EEw".to_string(),
+            ],
+            "Ew".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(
+            out.unwrap_err(),
+            HandErr::TooManyCopies {
+                tile: "Ew".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn too_many_copies_with_indicators() {
+        let out = Hand::new(
+            vec![
+                "1111m".to_string(),
+                "234p".to_string(),
+                "567s".to_string(),
+                "EEEw".to_string(),
+                "55s".to_string(),
+            ],
+            "5s".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.validate_tile_counts(&["9m".to_string()]), Ok(()));
+        assert_eq!(
+            out.validate_tile_counts(&["9m".to_string(), "1m".to_string()]),
+            Err(HandErr::TooManyCopies {
+                tile: "1m".to_string()
+            })
+        );
+        assert_eq!(
+            out.validate_tile_counts(&["5s".to_string(), "5s".to_string()]),
+            Err(HandErr::TooManyCopies {
+                tile: "5s".to_string()
+            })
+        );
+        assert!(matches!(
+            out.validate_tile_counts(&["11m".to_string()]),
+            Err(HandErr::InvalidGroup(_))
+        ));
+    }
+
     #[test]
     fn invalid_group_sequence_not_in_order() {
         let out = Hand::new(
@@ -2369,7 +2815,17 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidGroup);
+        assert_eq!(
+            out.unwrap_err(),
+            HandErr::InvalidGroup(InputErr {
+                index: Some(0),
+                input: "135m".to_string(),
+                offset: 0,
+                len: 3,
+                expected: "a triplet or a sequence in order".to_string(),
+                found: "`135`".to_string(),
+            })
+        );
     }
 
     #[test]
@@ -2386,7 +2842,17 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidSuit);
+        assert_eq!(
+            out.unwrap_err(),
+            HandErr::InvalidSuit(InputErr {
+                index: Some(4),
+                input: "ShSo".to_string(),
+                offset: 2,
+                len: 1,
+                expected: "a suit (m, p, s, w, d)".to_string(),
+                found: "`S`".to_string(),
+            })
+        );
     }
 
     #[test]
@@ -2403,7 +2869,67 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidGroup);
+        let err = out.unwrap_err();
+        assert!(matches!(err, HandErr::InvalidGroup(_)));
+        assert_eq!(err.input().unwrap().index, Some(0));
+        assert_eq!(err.input().unwrap().expected, "1 to 4 tiles");
+    }
+
+    #[test]
+    fn invalid_group_value() {
+        let out = Hand::new(
+            vec![
+                "SSSw".to_string(),
+                "7z9s".to_string(),
+                "WWWw".to_string(),
+                "NNNw".to_string(),
+                "EEw".to_string(),
+            ],
+            "Ew".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        );
+        let err = out.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Group found in group 2: expected a tile value (1-9, E, S, W, N, r, g, w), found `z`"
+        );
+        assert_eq!(err.input().unwrap().underline(), "7z9s\n ^");
+    }
+
+    #[test]
+    fn invalid_win_tile() {
+        let out = Hand::new(
+            vec![
+                "SSSw".to_string(),
+                "789s".to_string(),
+                "WWWw".to_string(),
+                "NNNw".to_string(),
+                "EEw".to_string(),
+            ],
+            "Ex".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        );
+        assert_eq!(
+            out.unwrap_err().to_string(),
+            "Invalid Suit found in `Ex`: expected a suit (m, p, s, w, d), found `x`"
+        );
+        let out = Hand::new(
+            vec![
+                "SSSw".to_string(),
+                "789s".to_string(),
+                "WWWw".to_string(),
+                "NNNw".to_string(),
+                "EEw".to_string(),
+            ],
+            "7s".to_string(),
+            "Ew".to_string(),
+            "Ew".to_string(),
+        );
+        let err = out.unwrap_err();
+        assert!(matches!(err, HandErr::InvalidShape(_)));
+        assert_eq!(err.input().unwrap().index, Some(4));
     }
 
     #[test]
@@ -2420,7 +2946,9 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidSuit);
+        let err = out.unwrap_err();
+        assert!(matches!(err, HandErr::InvalidSuit(_)));
+        assert_eq!(err.input().unwrap().found, "`j`");
     }
 
     #[test]
@@ -2431,14 +2959,16 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidShape);
+        assert!(matches!(out.unwrap_err(), HandErr::InvalidShape(_)));
         let out = Hand::new(
             vec!["SSSw".to_string()],
             "3s".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidShape);
+        let err = out.unwrap_err();
+        assert!(matches!(err, HandErr::InvalidShape(_)));
+        assert_eq!(err.input().unwrap().index, None);
     }
     #[test]
     fn hand_too_big() {
@@ -2456,7 +2986,9 @@ mod tests {
             "3s".to_string(),
             "3s".to_string(),
         );
-        assert_eq!(out.unwrap_err(), HandErr::InvalidShape);
+        let err = out.unwrap_err();
+        assert!(matches!(err, HandErr::InvalidShape(_)));
+        assert_eq!(err.input().unwrap().index, None);
     }
 }
 
@@ -2470,13 +3002,13 @@ mod tile_group_tests {
     fn identify_pair() {
         let out = Hand::new(
             vec![
-                "SSSw".to_string(),
-                "SSSw".to_string(),
-                "SSSw".to_string(),
+                "EEEw".to_string(),
+                "WWWw".to_string(),
+                "NNNw".to_string(),
                 "SSw".to_string(),
-                "SSSw".to_string(),
+                "rrrd".to_string(),
             ],
-            "Sw".to_string(),
+            "rd".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2493,12 +3025,12 @@ mod tile_group_tests {
         let out = Hand::new(
             vec![
                 "SSSw".to_string(),
-                "SSSw".to_string(),
-                "SSSw".to_string(),
-                "SSw".to_string(),
-                "SSSw".to_string(),
+                "EEEw".to_string(),
+                "WWWw".to_string(),
+                "NNw".to_string(),
+                "rrrd".to_string(),
             ],
-            "Sw".to_string(),
+            "rd".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2516,11 +3048,11 @@ mod tile_group_tests {
             vec![
                 "EEEEwo".to_string(),
                 "SSSw".to_string(),
-                "SSSw".to_string(),
-                "SSw".to_string(),
-                "SSSw".to_string(),
+                "WWWw".to_string(),
+                "NNw".to_string(),
+                "rrrd".to_string(),
             ],
-            "Sw".to_string(),
+            "rd".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2537,12 +3069,12 @@ mod tile_group_tests {
         let out = Hand::new(
             vec![
                 "rrrrd".to_string(),
-                "rrrrd".to_string(),
+                "ggggd".to_string(),
                 "SSw".to_string(),
-                "rrrrd".to_string(),
-                "rrrd".to_string(),
+                "wwwwd".to_string(),
+                "EEEw".to_string(),
             ],
-            "rd".to_string(),
+            "Ew".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2559,11 +3091,11 @@ mod tile_group_tests {
             vec![
                 "111m".to_string(),
                 "SSSw".to_string(),
-                "SSSw".to_string(),
-                "SSw".to_string(),
-                "SSSw".to_string(),
+                "EEEw".to_string(),
+                "WWw".to_string(),
+                "NNNw".to_string(),
             ],
-            "Sw".to_string(),
+            "Nw".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2580,11 +3112,11 @@ mod tile_group_tests {
             vec![
                 "789s".to_string(),
                 "SSSw".to_string(),
-                "SSw".to_string(),
-                "SSSw".to_string(),
-                "SSSw".to_string(),
+                "WWw".to_string(),
+                "EEEw".to_string(),
+                "NNNw".to_string(),
             ],
-            "Sw".to_string(),
+            "Nw".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
@@ -2601,11 +3133,11 @@ mod tile_group_tests {
             vec![
                 "234po".to_string(),
                 "SSSw".to_string(),
-                "SSw".to_string(),
-                "SSSw".to_string(),
-                "SSSw".to_string(),
+                "WWw".to_string(),
+                "EEEw".to_string(),
+                "NNNw".to_string(),
             ],
-            "Sw".to_string(),
+            "Nw".to_string(),
             "3s".to_string(),
             "3s".to_string(),
         )
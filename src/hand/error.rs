@@ -1,8 +1,11 @@
+/// Every error returned by the library.
+///
+/// Variants are grouped into an [`ErrorCategory`], and each has a stable [`code`](HandErr::code) for machine-readable output.
 #[derive(Debug, PartialEq)]
 pub enum HandErr {
-    InvalidGroup,
-    InvalidSuit,
-    InvalidShape,
+    InvalidGroup(InputErr),
+    InvalidSuit(InputErr),
+    InvalidShape(InputErr),
     NoYaku,
     NoHandTiles,
     NoWinTile,
@@ -11,25 +14,44 @@ pub enum HandErr {
     DoubleRiichiHaiteiIppatsu,
     DoubleRiichiHaiteiChankan,
     ChankanTsumo,
+    ChankanInvalidKan,
     RinshanKanWithoutKan,
     RinshanWithoutTsumo,
     RinshanIppatsu,
     NoHan,
     NoFu,
+    InvalidSeat,
+    RonFromSelf,
+    InvalidMultiRon,
+    LiableWinner,
+    InvalidPayment,
+    SanmaInvalidTile,
+    SanmaChii,
+    SanmaNorthWind,
+    KitaWithoutSanma,
+    TooManyCopies { tile: String },
+    InvalidLog(String),
+    GameOver,
 }
 
 impl std::fmt::Display for HandErr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::InvalidGroup => write!(f, "Invalid Group found"),
-            Self::InvalidSuit => write!(f, "Invalid Suit found"),
-            Self::InvalidShape => write!(f, "Invalid Hand Shape found"),
+            Self::InvalidGroup(input) => write!(f, "Invalid Group found {}", input),
+            Self::InvalidSuit(input) => write!(f, "Invalid Suit found {}", input),
+            Self::InvalidShape(input) => write!(f, "Invalid Hand Shape found {}", input),
             Self::NoYaku => write!(f, "No Yaku"),
             Self::NoHandTiles => write!(f, "No Hand Tiles given"),
             Self::NoWinTile => write!(f, "No Win Tile given"),
             Self::DuplicateRiichi => write!(f, "Cant Riichi and Double Riichi Simultaneously"),
             Self::IppatsuWithoutRiichi => write!(f, "Cant Ippatsu without Riichi"),
             Self::ChankanTsumo => write!(f, "Cant Tsumo and Chankan"),
+            Self::ChankanInvalidKan => {
+                write!(
+                    f,
+                    "Cant Chankan a closed kan without Kokushi, or any kan but an added kan"
+                )
+            }
             Self::RinshanKanWithoutKan => write!(f, "Cant Rinshan without Kan"),
             Self::RinshanWithoutTsumo => write!(f, "Cant Rinshan without Tsumo"),
             Self::RinshanIppatsu => write!(f, "Cant Rinshan and Ippatsu"),
@@ -37,10 +59,243 @@ impl std::fmt::Display for HandErr {
                 write!(f, "Cant Double Riichi, Ippatsu and Haitei")
             }
             Self::DoubleRiichiHaiteiChankan => {
-                write!(f, "Cant Double Riichi, Ippatsu and Haitei")
+                write!(f, "Cant Double Riichi, Haitei and Chankan")
             }
             Self::NoHan => write!(f, "No Han provided!"),
             Self::NoFu => write!(f, "No Fu provided!"),
+            Self::InvalidSeat => write!(f, "Invalid Seat given"),
+            Self::RonFromSelf => write!(f, "Cant Ron from yourself"),
+            Self::InvalidMultiRon => write!(f, "Multiple Ron must be on the same discard"),
+            Self::LiableWinner => write!(f, "Cant be liable for your own win"),
+            Self::InvalidPayment => write!(f, "Payment must have 5 amounts"),
+            Self::SanmaInvalidTile => write!(f, "Cant use 2-8 Manzu in Sanma"),
+            Self::SanmaChii => write!(f, "Cant Chii in Sanma"),
+            Self::SanmaNorthWind => write!(f, "Cant have a North seat or round in Sanma"),
+            Self::KitaWithoutSanma => write!(f, "Cant Kita outside of Sanma"),
+            Self::TooManyCopies { tile } => write!(f, "More than 4 copies of {} found", tile),
+            Self::InvalidLog(reason) => write!(f, "Invalid game log: {}", reason),
+            Self::GameOver => write!(f, "The game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for HandErr {}
+
+impl HandErr {
+    /// Get the category of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::{ErrorCategory, HandErr};
+    ///
+    /// assert_eq!(HandErr::NoYaku.category(), ErrorCategory::Score);
+    /// ```
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::InvalidGroup(_)
+            | Self::InvalidSuit(_)
+            | Self::NoHandTiles
+            | Self::NoWinTile
+            | Self::InvalidLog(_) => ErrorCategory::Parse,
+            Self::InvalidShape(_)
+            | Self::TooManyCopies { .. }
+            | Self::SanmaInvalidTile
+            | Self::SanmaChii => ErrorCategory::Shape,
+            Self::DuplicateRiichi
+            | Self::IppatsuWithoutRiichi
+            | Self::DoubleRiichiHaiteiIppatsu
+            | Self::DoubleRiichiHaiteiChankan
+            | Self::ChankanTsumo
+            | Self::ChankanInvalidKan
+            | Self::RinshanKanWithoutKan
+            | Self::RinshanWithoutTsumo
+            | Self::RinshanIppatsu
+            | Self::InvalidSeat
+            | Self::RonFromSelf
+            | Self::InvalidMultiRon
+            | Self::LiableWinner
+            | Self::InvalidPayment
+            | Self::SanmaNorthWind
+            | Self::KitaWithoutSanma
+            | Self::GameOver => ErrorCategory::Situation,
+            Self::NoYaku | Self::NoHan | Self::NoFu => ErrorCategory::Score,
+        }
+    }
+
+    /// Get the stable, machine-readable code of the error (ex: `invalid_group`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidGroup(_) => "invalid_group",
+            Self::InvalidSuit(_) => "invalid_suit",
+            Self::InvalidShape(_) => "invalid_shape",
+            Self::NoYaku => "no_yaku",
+            Self::NoHandTiles => "no_hand_tiles",
+            Self::NoWinTile => "no_win_tile",
+            Self::DuplicateRiichi => "duplicate_riichi",
+            Self::IppatsuWithoutRiichi => "ippatsu_without_riichi",
+            Self::DoubleRiichiHaiteiIppatsu => "double_riichi_haitei_ippatsu",
+            Self::DoubleRiichiHaiteiChankan => "double_riichi_haitei_chankan",
+            Self::ChankanTsumo => "chankan_tsumo",
+            Self::ChankanInvalidKan => "chankan_invalid_kan",
+            Self::RinshanKanWithoutKan => "rinshan_without_kan",
+            Self::RinshanWithoutTsumo => "rinshan_without_tsumo",
+            Self::RinshanIppatsu => "rinshan_ippatsu",
+            Self::NoHan => "no_han",
+            Self::NoFu => "no_fu",
+            Self::InvalidSeat => "invalid_seat",
+            Self::RonFromSelf => "ron_from_self",
+            Self::InvalidMultiRon => "invalid_multi_ron",
+            Self::LiableWinner => "liable_winner",
+            Self::InvalidPayment => "invalid_payment",
+            Self::SanmaInvalidTile => "sanma_invalid_tile",
+            Self::SanmaChii => "sanma_chii",
+            Self::SanmaNorthWind => "sanma_north_wind",
+            Self::KitaWithoutSanma => "kita_without_sanma",
+            Self::TooManyCopies { .. } => "too_many_copies",
+            Self::InvalidLog(_) => "invalid_log",
+            Self::GameOver => "game_over",
+        }
+    }
+
+    /// Get the offending input, if the error was caused by invalid input.
+    pub fn input(&self) -> Option<&InputErr> {
+        match self {
+            Self::InvalidGroup(input) | Self::InvalidSuit(input) | Self::InvalidShape(input) => {
+                Some(input)
+            }
+            _ => None,
+        }
+    }
+
+    /// Place the offending input inside of the larger `input` it was taken from, starting at `offset`.
+    pub(crate) fn within(mut self, input: &str, offset: usize) -> Self {
+        if let Self::InvalidGroup(err) | Self::InvalidSuit(err) | Self::InvalidShape(err) =
+            &mut self
+        {
+            err.input = input.to_string();
+            err.offset += offset;
+        }
+        self
+    }
+
+    /// Record the index of the hand group that caused the error.
+    pub(crate) fn in_group(mut self, index: usize) -> Self {
+        if let Self::InvalidGroup(input) | Self::InvalidSuit(input) | Self::InvalidShape(input) =
+            &mut self
+        {
+            input.index = Some(index);
+        }
+        self
+    }
+}
+
+/// The broad kind of a [`HandErr`], for callers that only care about what went wrong in general.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The input could not be read as tiles.
+    Parse,
+    /// The tiles do not form a possible winning hand.
+    Shape,
+    /// The conditions of the win are impossible (ex: ippatsu without riichi).
+    Situation,
+    /// The hand or han/fu can not be scored.
+    Score,
+}
+
+impl ErrorCategory {
+    /// Get the stable, machine-readable name of the category (ex: `parse`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Shape => "shape",
+            Self::Situation => "situation",
+            Self::Score => "score",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => write!(f, "Parse Error"),
+            Self::Shape => write!(f, "Shape Error"),
+            Self::Situation => write!(f, "Situation Error"),
+            Self::Score => write!(f, "Score Error"),
+        }
+    }
+}
+
+/// The location and details of invalid input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputErr {
+    /// Index of the group in the hand tiles, if the input is one of the hand groups.
+    pub index: Option<usize>,
+    /// The original input.
+    pub input: String,
+    /// Character offset of the invalid part of the input.
+    pub offset: usize,
+    /// Number of characters of the invalid part of the input.
+    pub len: usize,
+    /// Description of what was expected.
+    pub expected: String,
+    /// Description of what was found.
+    pub found: String,
+}
+
+impl InputErr {
+    /// Create the details of invalid input, where the invalid part is `len` characters starting at `offset`.
+    pub fn new(input: &str, offset: usize, len: usize, expected: &str) -> Self {
+        let found: String = input.chars().skip(offset).take(len).collect();
+        Self {
+            index: None,
+            input: input.to_string(),
+            offset,
+            len,
+            expected: expected.to_string(),
+            found: format!("`{}`", found),
+        }
+    }
+
+    /// Create the details of invalid input spanning the whole input.
+    pub fn whole(input: &str, expected: &str, found: &str) -> Self {
+        Self {
+            index: None,
+            input: input.to_string(),
+            offset: 0,
+            len: input.chars().count(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    /// Render the input with the invalid part underlined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::InputErr;
+    ///
+    /// let err = InputErr::new("7z9s", 1, 1, "a tile value");
+    ///
+    /// assert_eq!(err.underline(), "7z9s\n ^");
+    /// ```
+    pub fn underline(&self) -> String {
+        format!(
+            "{}\n{}{}",
+            self.input,
+            " ".repeat(self.offset),
+            "^".repeat(self.len.max(1))
+        )
+    }
+}
+
+impl std::fmt::Display for InputErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "in group {}", index + 1)?,
+            None => write!(f, "in `{}`", self.input)?,
         }
+        write!(f, ": expected {}, found {}", self.expected, self.found)
     }
 }
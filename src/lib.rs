@@ -1,9 +1,16 @@
 pub mod calc;
 pub mod fu;
+pub mod game;
 pub mod hand;
 pub mod limit_hand;
+pub mod notation;
+pub mod replay;
+pub mod settlement;
+pub mod standings;
 pub mod suit;
 pub mod tile_group;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod yaku;
 
 /// Characters that represent terminal or honor tiles.
@@ -36,13 +36,13 @@ impl LimitHands {
         if han <= 5 {
             Some(Self::Mangan)
         } else if han <= 7 {
-            return Some(Self::Haneman);
+            Some(Self::Haneman)
         } else if han <= 10 {
-            return Some(Self::Baiman);
+            Some(Self::Baiman)
         } else if han <= 12 {
-            return Some(Self::Sanbaiman);
+            Some(Self::Sanbaiman)
         } else {
-            return Some(Self::KazoeYakuman);
+            Some(Self::KazoeYakuman)
         }
     }
 
@@ -96,3 +96,15 @@ impl LimitHands {
         }
     }
 }
+
+impl std::fmt::Display for LimitHands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mangan => write!(f, "Mangan"),
+            Self::Haneman => write!(f, "Haneman"),
+            Self::Baiman => write!(f, "Baiman"),
+            Self::Sanbaiman => write!(f, "Sanbaiman"),
+            Self::KazoeYakuman => write!(f, "Kazoe Yakuman"),
+        }
+    }
+}
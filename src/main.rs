@@ -1,15 +1,31 @@
 use std::ffi::OsString;
 use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use clap::Parser;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use mahc::calc;
 use mahc::fu::Fu;
-use mahc::hand::error::HandErr;
+use mahc::game::{Game, GameLength, GameRules, RoundEnd};
+use mahc::hand::error::{ErrorCategory, HandErr};
+use mahc::hand::Hand;
+use mahc::limit_hand::LimitHands;
+use mahc::notation::{self, MpszHand};
+use mahc::replay::{majsoul, tenhou, Discrepancy, LoggedWin};
+use mahc::settlement::{Win, PLAYER_COUNT};
+use mahc::standings::{self, StandingsRules};
+use mahc::tile_group::MeldKind;
 use mahc::yaku::Yaku;
 use serde_json::json;
 
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "tui")]
+mod tui;
+
 /// riichi mahjong calculator tool
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None)]
 pub struct Args {
     /// Hand tiles
@@ -20,6 +36,18 @@ pub struct Args {
     #[arg(short, long)]
     win: Option<String>,
 
+    /// Notation of the hand tiles
+    #[arg(long, value_enum, default_value_t = Notation::Auto)]
+    notation: Notation,
+
+    /// show the hand as unicode mahjong tiles
+    #[arg(long, default_value_t = false)]
+    unicode: bool,
+
+    /// Dora indicator tiles, counted when checking for more than 4 copies of a tile
+    #[clap(long, value_delimiter = ' ', num_args = 1..)]
+    indicators: Option<Vec<String>>,
+
     /// Han from dora
     #[arg(short, long, default_value_t = 0)]
     dora: u16,
@@ -60,6 +88,10 @@ pub struct Args {
     #[arg(long, default_value_t = false)]
     chankan: bool,
 
+    /// kind of kan robbed by chankan
+    #[arg(long, value_enum, default_value_t = RobbedKan::Shouminkan)]
+    robbed_kan: RobbedKan,
+
     /// is tenhou/chihou
     #[arg(long, default_value_t = false)]
     tenhou: bool,
@@ -72,19 +104,197 @@ pub struct Args {
     #[arg(short, long, default_value = None, value_delimiter = ' ', num_args = 2)]
     manual: Option<Vec<u16>>,
 
-    /// file input
+    /// file input, or `-` to read from stdin
     #[arg(short, long, default_value = None)]
     file: Option<String>,
 
+    /// csv file input, scoring each row and writing it back with the score appended (`-` for stdin)
+    #[arg(long, default_value = None)]
+    csv: Option<String>,
+
+    /// tsv file input, like --csv but tab separated
+    #[arg(long, default_value = None)]
+    tsv: Option<String>,
+
+    /// tenhou json log input, checking the score of every win against the log (`-` for stdin)
+    #[arg(long, default_value = None)]
+    tenhou_log: Option<String>,
+
+    /// mahjong soul paipu json input, scoring and printing every win of the game (`-` for stdin)
+    #[arg(long, default_value = None)]
+    majsoul_log: Option<String>,
+
     /// stdout as json
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     json: bool,
+
+    /// stop at the first hand that fails in file input
+    #[arg(long, default_value_t = false)]
+    fail_fast: bool,
+
+    /// sanma (three-player) mode, with the tsumo payment rule
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "tsumo-loss")]
+    sanma: Option<SanmaRule>,
+
+    /// north tiles set aside as dora (sanma only)
+    #[arg(long, default_value_t = 0)]
+    kita: u16,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Modes other than scoring hands.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// final standings with uma and oka, from the final scores of seats 0-3 (ties go to the lower seat)
+    Standings {
+        /// final scores, in seat order
+        #[arg(num_args = 4, required = true, allow_negative_numbers = true)]
+        scores: Vec<i32>,
+
+        /// uma for first to fourth place, in thousands of points
+        #[arg(long, num_args = 4, allow_negative_numbers = true, action = ArgAction::Set, default_values_t = [15, 5, -5, -15])]
+        uma: Vec<i32>,
+
+        /// points each player started with
+        #[arg(long, default_value_t = 25000)]
+        starting: i32,
+
+        /// points each player is measured against, the difference with the starting points going to first place (oka)
+        #[arg(long, default_value_t = 30000)]
+        returning: i32,
+    },
+    /// keep score of a whole game interactively (type `help` for the commands)
+    Repl {
+        /// winds played
+        #[arg(long, value_enum, default_value_t = Length::South)]
+        length: Length,
+
+        /// session saved as json to start from
+        #[arg(long)]
+        load: Option<String>,
+    },
+    /// build a hand from a tile palette in the terminal, scoring it as it changes
+    #[cfg(feature = "tui")]
+    Tui,
+    /// answer json requests over http on localhost, with the same json as --json
+    #[cfg(feature = "serve")]
+    Serve {
+        /// port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+}
+
+/// Winds played in a game.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Length {
+    /// east round only (tonpuusen)
+    East,
+    /// east and south rounds (hanchan)
+    South,
+}
+
+impl From<Length> for GameLength {
+    fn from(length: Length) -> Self {
+        match length {
+            Length::East => Self::East,
+            Length::South => Self::South,
+        }
+    }
+}
+
+/// Notation of the hand tiles.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    /// mpsz if the tiles can only be read as mpsz, otherwise mahc
+    Auto,
+    /// groups of one suit (ex: 123m EEEw rrrdo)
+    Mahc,
+    /// tenhou/mahjong soul style (ex: 123m456p789s11z), with c/p/k/a in front of chi/pon/kan/closed kan calls. unicode tiles (ex: 🀇🀈🀉) are read as mpsz
+    Mpsz,
+}
+
+/// Kind of kan robbed by chankan.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum RobbedKan {
+    /// an added kan
+    Shouminkan,
+    /// a closed kan, only robbable for kokushi musou
+    Ankan,
+}
+
+impl From<RobbedKan> for MeldKind {
+    fn from(kan: RobbedKan) -> Self {
+        match kan {
+            RobbedKan::Shouminkan => Self::Shouminkan,
+            RobbedKan::Ankan => Self::Ankan,
+        }
+    }
+}
+
+/// Score breakdown of a hand, as returned by [`calc::get_hand_score()`].
+type HandScore = (Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool);
+
+/// Tsumo payment rule for sanma.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum SanmaRule {
+    /// the missing north player's share is not paid
+    TsumoLoss,
+    /// the missing north player's share is split between the other two players
+    NorthBisection,
+}
+
+impl From<SanmaRule> for calc::SanmaTsumo {
+    fn from(rule: SanmaRule) -> Self {
+        match rule {
+            SanmaRule::TsumoLoss => Self::TsumoLoss,
+            SanmaRule::NorthBisection => Self::NorthBisection,
+        }
+    }
+}
+
+/// Process exit codes, so scripts can tell failures apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Success = 0,
+    /// invalid command line arguments
+    Usage = 2,
+    /// input could not be read as tiles
+    ParseError = 3,
+    /// tiles do not form a possible winning hand
+    InvalidShape = 4,
+    /// the win conditions are impossible
+    InvalidSituation = 5,
+    /// no yaku, han or fu to score
+    NoYaku = 6,
+    /// a file could not be read
+    IoError = 7,
+    /// some hands in file input failed
+    PartialBatchFailure = 8,
+    /// a batch file includes itself, or includes files too deep
+    InvalidInclude = 9,
+}
+
+impl From<&HandErr> for Exit {
+    fn from(e: &HandErr) -> Self {
+        match e.category() {
+            ErrorCategory::Parse => Self::ParseError,
+            ErrorCategory::Shape => Self::InvalidShape,
+            ErrorCategory::Situation => Self::InvalidSituation,
+            ErrorCategory::Score => Self::NoYaku,
+        }
+    }
 }
 
 pub fn parse_calculator(args: &Args) -> Result<String, HandErr> {
     let honba = args.ba;
     let hanandfu = args.manual.clone().unwrap();
-    let scores = calc::calculate(&hanandfu, honba)?;
+    let scores = match args.sanma {
+        Some(rule) => calc::calculate_sanma(&hanandfu, honba, rule.into())?,
+        None => calc::calculate(&hanandfu, honba)?,
+    };
     let printout: Result<String, HandErr> = if args.json {
         Ok(json_calc_out(scores, honba, hanandfu))
     } else {
@@ -93,6 +303,77 @@ pub fn parse_calculator(args: &Args) -> Result<String, HandErr> {
     printout
 }
 pub fn parse_hand(args: &Args) -> Result<String, HandErr> {
+    let (args, result) = score_any_hand(args)?;
+
+    let printout: String = if args.json {
+        json_hand_out(result, &args)
+    } else {
+        default_hand_out(result, &args)
+    };
+    Ok(printout)
+}
+
+/// Score the hand in whichever notation it is written, also returning the arguments converted to the mahc notation.
+fn score_any_hand(args: &Args) -> Result<(Args, HandScore), HandErr> {
+    let is_mpsz = match args.notation {
+        Notation::Auto => args
+            .tiles
+            .as_deref()
+            .is_some_and(|tiles| notation::is_mpsz(tiles) || notation::is_unicode(tiles)),
+        Notation::Mahc => false,
+        Notation::Mpsz => true,
+    };
+    if is_mpsz {
+        score_mpsz_hand(args)
+    } else {
+        Ok((args.clone(), score_hand(args)?))
+    }
+}
+
+/// Score every way to read a hand written in the mpsz notation, keeping the highest scoring one.
+///
+/// Also returns the arguments converted to the mahc notation, with red fives added to the dora.
+fn score_mpsz_hand(args: &Args) -> Result<(Args, HandScore), HandErr> {
+    let Some(tiles) = &args.tiles else {
+        return Err(HandErr::NoHandTiles);
+    };
+    let tiles: Vec<String> = tiles.iter().map(|t| notation::from_unicode(t)).collect();
+    let mut hand = MpszHand::parse(&tiles.join(" "))?;
+    if let Some(win) = &args.win {
+        hand = hand.with_win(win)?;
+    }
+
+    let mut best: Option<(Args, HandScore)> = None;
+    let mut error = None;
+    for tiles in hand.arrangements()? {
+        let mut arranged = args.clone();
+        arranged.notation = Notation::Mahc;
+        arranged.tiles = Some(tiles);
+        arranged.win = Some(hand.win());
+        arranged.dora = args.dora.saturating_add(hand.red_fives());
+        arranged.seat = notation::to_hand_tile(&args.seat)?;
+        arranged.prev = notation::to_hand_tile(&args.prev)?;
+        match score_hand(&arranged) {
+            Ok(result) => {
+                let rank = |result: &HandScore| (result.0[2], result.3[0], result.3[1]);
+                if best.as_ref().is_none_or(|(_, b)| rank(&result) > rank(b)) {
+                    best = Some((arranged, result));
+                }
+            }
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+    }
+    match (best, error) {
+        (Some(best), _) => Ok(best),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(HandErr::NoHandTiles),
+    }
+}
+
+/// Check the round context and score the hand written in the mahc notation.
+fn score_hand(args: &Args) -> Result<HandScore, HandErr> {
     if args.tiles.is_none() {
         return Err(HandErr::NoHandTiles);
     }
@@ -120,31 +401,63 @@ pub fn parse_hand(args: &Args) -> Result<String, HandErr> {
     if args.doubleriichi && args.haitei && args.chankan {
         return Err(HandErr::DoubleRiichiHaiteiChankan);
     }
-    let result = calc::get_hand_score(
-        args.tiles.clone().unwrap(),
-        args.win.clone().unwrap(),
-        args.dora,
-        args.seat.clone(),
-        args.prev.clone(),
-        args.tsumo,
-        args.riichi,
-        args.doubleriichi,
-        args.ippatsu,
-        args.haitei,
-        args.rinshan,
-        args.chankan,
-        args.tenhou,
-        args.ba,
-    )?;
+    if args.sanma.is_none() && args.kita != 0 {
+        return Err(HandErr::KitaWithoutSanma);
+    }
+    if args.indicators.is_some() || args.chankan {
+        let hand = Hand::new(
+            args.tiles.clone().unwrap(),
+            args.win.clone().unwrap(),
+            args.prev.clone(),
+            args.seat.clone(),
+        )?;
+        if let Some(indicators) = &args.indicators {
+            hand.validate_tile_counts(indicators)?;
+        }
+        if args.chankan {
+            hand.validate_chankan(args.robbed_kan.into())?;
+        }
+    }
+    let result = match args.sanma {
+        Some(rule) => calc::get_sanma_hand_score(
+            args.tiles.clone().unwrap(),
+            args.win.clone().unwrap(),
+            args.dora,
+            args.kita,
+            args.seat.clone(),
+            args.prev.clone(),
+            args.tsumo,
+            args.riichi,
+            args.doubleriichi,
+            args.ippatsu,
+            args.haitei,
+            args.rinshan,
+            args.chankan,
+            args.tenhou,
+            args.ba,
+            rule.into(),
+        )?,
+        None => calc::get_hand_score(
+            args.tiles.clone().unwrap(),
+            args.win.clone().unwrap(),
+            args.dora,
+            args.seat.clone(),
+            args.prev.clone(),
+            args.tsumo,
+            args.riichi,
+            args.doubleriichi,
+            args.ippatsu,
+            args.haitei,
+            args.rinshan,
+            args.chankan,
+            args.tenhou,
+            args.ba,
+        )?,
+    };
 
     //TODO VALIDATION (i dont care enough yet)
 
-    let printout: String = if args.json {
-        json_hand_out(result, args)
-    } else {
-        default_hand_out(result, args)
-    };
-    Ok(printout)
+    Ok(result)
 }
 pub fn json_calc_out(result: Vec<u32>, honba: u16, hanandfu: Vec<u16>) -> String {
     let out = json!({
@@ -183,7 +496,7 @@ pub fn json_hand_out(
     result: (Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool),
     args: &Args,
 ) -> String {
-    let out = json!({
+    let mut out = json!({
         "han" : result.3[0],
         "fu" : result.3[1],
         "honba" : args.ba,
@@ -204,13 +517,33 @@ pub fn json_hand_out(
             }
         }
     });
+    if args.sanma.is_some() {
+        out["kita"] = json!(args.kita);
+    }
+    if args.unicode {
+        out["unicode"] = json!(unicode_hand(args));
+    }
     out.to_string()
 }
+/// Render the hand tiles of the arguments as unicode mahjong tiles.
+fn unicode_hand(args: &Args) -> String {
+    Hand::new(
+        args.tiles.clone().unwrap_or_default(),
+        args.win.clone().unwrap_or_default(),
+        args.prev.clone(),
+        args.seat.clone(),
+    )
+    .map(|hand| hand.to_unicode())
+    .unwrap_or_default()
+}
 pub fn default_hand_out(
     result: (Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool),
     args: &Args,
 ) -> String {
     let mut out: String = String::new();
+    if args.unicode {
+        out.push_str(format!("\n{}", unicode_hand(args)).as_str());
+    }
     if !result.1[0].is_yakuman() {
         if args.ba != 0 {
             out.push_str(
@@ -236,6 +569,9 @@ pub fn default_hand_out(
     if !result.1[0].is_yakuman() && args.dora != 0 {
         out.push_str(format!("\nDora: {}", args.dora).as_str());
     }
+    if !result.1[0].is_yakuman() && args.kita != 0 {
+        out.push_str(format!("\nKita: {}", args.kita).as_str());
+    }
     out.push_str("\nYaku: ");
     for i in &result.1 {
         out.push_str(format!("\n  {}", i.to_string(result.4)).as_str());
@@ -248,56 +584,1030 @@ pub fn default_hand_out(
     }
     out
 }
-pub fn parse_file(args: &Args) {
-    let string = fs::read_to_string(args.file.as_ref().unwrap());
-    if string.is_err() {
-        eprintln!("Error: Unable to read file {}", args.file.as_ref().unwrap());
-        return;
+/// File input path that reads from stdin instead.
+const STDIN_PATH: &str = "-";
+
+/// How deep batch files can include other batch files.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Tally of the hands run from file input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Get the lines of a batch file to run, with their line number, skipping blank lines and `#` comments.
+pub fn batch_lines(contents: &str) -> Vec<(usize, &str)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Run every line of the file, then print a summary of the results.
+pub fn parse_file(args: &Args) -> Exit {
+    let mut summary = BatchSummary::default();
+    let exit = run_file(args, &mut summary, &mut Vec::new());
+    if args.json {
+        println!(
+            "{}",
+            json!({
+                "summary" : {
+                    "total" : summary.succeeded + summary.failed,
+                    "succeeded" : summary.succeeded,
+                    "failed" : summary.failed
+                }
+            })
+        );
+    } else {
+        println!(
+            "\n{} succeeded, {} failed",
+            summary.succeeded, summary.failed
+        );
+    }
+    exit
+}
+
+/// Read a batch file, or stdin when the path is `-`.
+fn read_batch(path: &str) -> std::io::Result<String> {
+    if path == STDIN_PATH {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Print that a file could not be read, to stdout as json when `json` is set.
+fn print_read_error(path: &str, json: bool) -> Exit {
+    let message = format!("Unable to read file {}", path);
+    if json {
+        let error = json_failure("io_error", "io", &message, json!(path));
+        println!("{}", json!({ "error": error }));
+    } else {
+        eprintln!("Error: {}", message);
+    }
+    Exit::IoError
+}
+
+/// Resolve a file included from a batch file relative to the directory of the including file.
+fn resolve_include(including: &str, file: &str) -> String {
+    let path = Path::new(file);
+    if including == STDIN_PATH || file == STDIN_PATH || path.is_absolute() {
+        return file.to_string();
+    }
+    match Path::new(including).parent() {
+        Some(dir) => dir.join(path).to_string_lossy().into_owned(),
+        None => file.to_string(),
+    }
+}
+
+/// Check that the batch file can be included from the files in `includes`, returning the error code and message if not.
+fn check_include(file: &str, includes: &[PathBuf]) -> Result<(), (&'static str, String)> {
+    if includes.len() >= MAX_INCLUDE_DEPTH {
+        return Err((
+            "include_depth",
+            format!("Files are included more than {} deep", MAX_INCLUDE_DEPTH),
+        ));
     }
-    let string = string.unwrap();
-    let lines = string.lines();
-    for string in lines {
-        if string.is_empty() {
-            continue;
+    if let Ok(canonical) = fs::canonicalize(file) {
+        if let Some(start) = includes.iter().position(|p| *p == canonical) {
+            let chain: Vec<String> = includes[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err((
+                "include_cycle",
+                format!("Include cycle: {}", chain.join(" -> ")),
+            ));
         }
+    }
+    Ok(())
+}
+
+/// Run every line of a batch file, where `includes` are the files currently being run that included it.
+fn run_file(args: &Args, summary: &mut BatchSummary, includes: &mut Vec<PathBuf>) -> Exit {
+    let file = args.file.as_ref().unwrap();
+    let path = if file == STDIN_PATH { "<stdin>" } else { file };
+    let Ok(contents) = read_batch(file) else {
+        summary.failed += 1;
+        return print_read_error(path, args.json);
+    };
+
+    let canonical = fs::canonicalize(file).ok().filter(|_| file != STDIN_PATH);
+    if let Some(canonical) = &canonical {
+        includes.push(canonical.clone());
+    }
+    let exit = run_lines(args, path, &contents, summary, includes);
+    if canonical.is_some() {
+        includes.pop();
+    }
+    exit
+}
+
+fn run_lines(
+    args: &Args,
+    path: &str,
+    contents: &str,
+    summary: &mut BatchSummary,
+    includes: &mut Vec<PathBuf>,
+) -> Exit {
+    let file = args.file.as_ref().unwrap();
+    let mut exit = Exit::Success;
+    for (line, source) in batch_lines(contents) {
         let mut current_line_args = vec![OsString::from("mahc")];
-        for arg in string.split_whitespace() {
+        for arg in source.split_whitespace() {
             current_line_args.push(arg.into());
         }
-        let args = Args::parse_from(&current_line_args);
-        if args.file.is_some() {
-            parse_file(&args);
-        } else if args.manual.is_some() {
-            let result = parse_calculator(&args);
-            printout(result);
-        } else {
-            let result = parse_hand(&args);
-            printout(result);
+        let line_exit = match Args::try_parse_from(&current_line_args) {
+            Ok(mut line_args) => {
+                line_args.json |= args.json;
+                line_args.fail_fast |= args.fail_fast;
+                if let Some(include) = &line_args.file {
+                    let include = resolve_include(file, include);
+                    match check_include(&include, includes) {
+                        Ok(()) => {
+                            line_args.file = Some(include);
+                            run_file(&line_args, summary, includes)
+                        }
+                        Err((code, message)) => {
+                            summary.failed += 1;
+                            print_line_failure(
+                                path, line, source, code, "include", &message, args.json,
+                            );
+                            Exit::InvalidInclude
+                        }
+                    }
+                } else {
+                    let result = evaluate(&line_args);
+                    if result.is_ok() {
+                        summary.succeeded += 1;
+                    } else {
+                        summary.failed += 1;
+                    }
+                    print_line(path, line, source, result, line_args.json)
+                }
+            }
+            Err(e) => {
+                summary.failed += 1;
+                print_usage_error(path, line, source, &e, args.json)
+            }
+        };
+        if line_exit != Exit::Success {
+            if args.fail_fast {
+                return line_exit;
+            }
+            exit = Exit::PartialBatchFailure;
         }
     }
+    exit
 }
-pub fn printout(result: Result<String, HandErr>) {
+
+/// Print the result of one line of a batch file, tagged with where it came from.
+fn print_line(
+    path: &str,
+    line: usize,
+    source: &str,
+    result: Result<String, HandErr>,
+    json: bool,
+) -> Exit {
     match result {
+        Ok(o) if json => {
+            let result: serde_json::Value = serde_json::from_str(&o).unwrap();
+            println!(
+                "{}",
+                json!({ "file": path, "line": line, "source": source, "result": result })
+            );
+            Exit::Success
+        }
         Ok(o) => {
-            println!("{}", o);
+            println!("{}:{}: {}{}", path, line, source, o);
+            Exit::Success
+        }
+        Err(e) if json => {
+            println!(
+                "{}",
+                json!({ "file": path, "line": line, "source": source, "error": json_error(&e) })
+            );
+            Exit::from(&e)
+        }
+        Err(e) => {
+            eprintln!("{}:{}: {}", path, line, source);
+            print_error(&e);
+            Exit::from(&e)
+        }
+    }
+}
+
+/// Print the arguments error of one line of a batch file.
+fn print_usage_error(path: &str, line: usize, source: &str, e: &clap::Error, json: bool) -> Exit {
+    print_line_failure(
+        path,
+        line,
+        source,
+        "invalid_arguments",
+        "usage",
+        &usage_message(e),
+        json,
+    );
+    Exit::Usage
+}
+
+/// Get the first line of an arguments error, without its `error: ` prefix.
+fn usage_message(e: &clap::Error) -> String {
+    let rendered = e.to_string();
+    rendered
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches("error: ")
+        .to_string()
+}
+
+/// Print a failure of one line of a batch file that is not a hand error.
+fn print_line_failure(
+    path: &str,
+    line: usize,
+    source: &str,
+    code: &str,
+    category: &str,
+    message: &str,
+    json: bool,
+) {
+    if json {
+        let error = json_failure(code, category, message, json!(null));
+        println!(
+            "{}",
+            json!({ "file": path, "line": line, "source": source, "error": error })
+        );
+    } else {
+        eprintln!("{}:{}: {}", path, line, source);
+        eprintln!("Error: {}", message);
+    }
+}
+
+/// Score the hand or han and fu given by the arguments.
+pub fn evaluate(args: &Args) -> Result<String, HandErr> {
+    if args.manual.is_some() {
+        parse_calculator(args)
+    } else {
+        parse_hand(args)
+    }
+}
+
+/// Columns appended to each row of csv/tsv input.
+const TABLE_COLUMNS: [&str; 10] = [
+    "han",
+    "fu",
+    "yaku",
+    "limit",
+    "dealer_ron",
+    "dealer_tsumo",
+    "non_dealer_ron",
+    "non_dealer_tsumo_non_dealer",
+    "non_dealer_tsumo_dealer",
+    "error",
+];
+
+/// Columns of csv/tsv input holding argument values, with the argument they are given to.
+const TABLE_ARGUMENTS: [(&str, &str); 10] = [
+    ("tiles", "tiles"),
+    ("win", "win"),
+    ("notation", "notation"),
+    ("indicators", "indicators"),
+    ("dora", "dora"),
+    ("seat", "seat"),
+    ("prev", "prev"),
+    ("honba", "ba"),
+    ("robbed_kan", "robbed-kan"),
+    ("kita", "kita"),
+];
+
+/// Columns of csv/tsv input holding flags.
+const TABLE_FLAGS: [&str; 8] = [
+    "tsumo",
+    "riichi",
+    "doubleriichi",
+    "ippatsu",
+    "haitei",
+    "rinshan",
+    "chankan",
+    "tenhou",
+];
+
+/// Score every row of csv/tsv input, writing the rows back to stdout with the score appended.
+///
+/// A file that can't be read or isn't valid csv/tsv is reported to stdout as json when `json` is set.
+pub fn parse_table(path: &str, delimiter: u8, json: bool) -> Exit {
+    let Ok(contents) = read_batch(path) else {
+        return print_read_error(path, json);
+    };
+    match score_table(&contents, delimiter, std::io::stdout()) {
+        Ok(0) => Exit::Success,
+        Ok(_) => Exit::PartialBatchFailure,
+        Err(e) if json => {
+            let error = if e.is_io_error() {
+                json_failure("io_error", "io", &e.to_string(), json!(path))
+            } else {
+                json_failure("invalid_table", "parse", &e.to_string(), json!(path))
+            };
+            println!("{}", json!({ "error": error }));
+            Exit::IoError
         }
         Err(e) => {
             eprintln!("Error: {}", e);
+            Exit::IoError
+        }
+    }
+}
+
+/// Score every row of csv/tsv `contents`, returning the number of rows that failed.
+///
+/// The header names the columns of the hand: `tiles` (space separated groups, in any notation), `win`, `notation`,
+/// `indicators`, `seat`, `prev`, `dora`, `honba`, `robbed_kan`, `kita`, `sanma` (a flag or the tsumo payment rule),
+/// and the flags `tsumo`, `riichi`, `doubleriichi`, `ippatsu`, `haitei`, `rinshan`, `chankan` and `tenhou`.
+/// Other columns are kept as is.
+pub fn score_table<W: std::io::Write>(
+    contents: &str,
+    delimiter: u8,
+    out: W,
+) -> Result<usize, csv::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(out);
+
+    let header = reader.headers()?.clone();
+    let mut out_header = header.clone();
+    out_header.extend(TABLE_COLUMNS);
+    writer.write_record(&out_header)?;
+
+    let mut failed = 0;
+    for row in reader.records() {
+        let row = row?;
+        let mut out_row = row.clone();
+        match score_row(&header, &row) {
+            Ok(score) => {
+                out_row.extend(&score);
+                out_row.push_field("");
+            }
+            Err(e) => {
+                failed += 1;
+                out_row.extend(&vec![String::new(); TABLE_COLUMNS.len() - 1]);
+                out_row.push_field(&e);
+            }
+        }
+        writer.write_record(&out_row)?;
+    }
+    writer.flush()?;
+    Ok(failed)
+}
+
+/// Score one row of csv/tsv input, giving the values of the score columns.
+///
+/// The columns are read as the arguments of the same name, so rows are checked and scored like `--tiles`.
+fn score_row(header: &csv::StringRecord, row: &csv::StringRecord) -> Result<Vec<String>, String> {
+    let field = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .and_then(|i| row.get(i))
+            .map(str::trim)
+            .unwrap_or_default()
+    };
+    let flag = |name: &str| -> Result<bool, String> {
+        match field(name).to_lowercase().as_str() {
+            "" | "0" | "false" | "no" | "n" => Ok(false),
+            "1" | "true" | "yes" | "y" | "x" => Ok(true),
+            value => Err(format!("Invalid {} `{}`", name, value)),
+        }
+    };
+
+    let mut argv = vec!["mahc".to_string()];
+    for (column, arg) in TABLE_ARGUMENTS {
+        let value = field(column).split_whitespace().collect::<Vec<&str>>();
+        if !value.is_empty() {
+            argv.push(format!("--{}={}", arg, value.join(" ")));
+        }
+    }
+    for name in TABLE_FLAGS {
+        if flag(name)? {
+            argv.push(format!("--{}", name));
+        }
+    }
+    // a sanma column holds either a flag or the tsumo payment rule
+    match flag("sanma") {
+        Ok(true) => argv.push("--sanma".to_string()),
+        Ok(false) => {}
+        Err(_) => argv.push(format!("--sanma={}", field("sanma"))),
+    }
+    let args = Args::try_parse_from(argv).map_err(|e| usage_message(&e))?;
+    let (_, (scores, yaku, _, hanandfu, is_open)) =
+        score_any_hand(&args).map_err(|e| e.to_string())?;
+
+    let limit = if yaku.iter().any(|y| y.is_yakuman()) {
+        "Yakuman".to_string()
+    } else {
+        LimitHands::get_limit_hand(hanandfu[0], hanandfu[1])
+            .map(|l| l.to_string())
+            .unwrap_or_default()
+    };
+    let mut out = vec![
+        hanandfu[0].to_string(),
+        hanandfu[1].to_string(),
+        yaku.iter()
+            .map(|y| y.to_string(is_open))
+            .collect::<Vec<String>>()
+            .join("; "),
+        limit,
+    ];
+    out.extend(scores.iter().map(|s| s.to_string()));
+    Ok(out)
+}
+
+/// Score every win of a Tenhou log, printing any difference with the logged score.
+pub fn parse_tenhou_log(path: &str, json: bool) -> Exit {
+    let Ok(contents) = read_batch(path) else {
+        return print_read_error(path, json);
+    };
+    let wins = match tenhou::parse(&contents) {
+        Ok(wins) => wins,
+        Err(e) => return printout(Err(e), json),
+    };
+
+    let mut differed = 0;
+    for win in &wins {
+        let discrepancies = win.verify();
+        if !discrepancies.is_empty() {
+            differed += 1;
+        }
+        println!("{}", replay_line(win, &discrepancies, json));
+    }
+
+    if json {
+        println!(
+            "{}",
+            json!({ "summary": { "total": wins.len(), "matched": wins.len() - differed, "differed": differed } })
+        );
+    } else {
+        println!("\n{} matched, {} differed", wins.len() - differed, differed);
+    }
+    if differed == 0 {
+        Exit::Success
+    } else {
+        Exit::PartialBatchFailure
+    }
+}
+
+/// Describe a replayed win and its differences with the logged score.
+fn replay_line(win: &LoggedWin, discrepancies: &[Discrepancy], json: bool) -> String {
+    if json {
+        let discrepancies: Vec<serde_json::Value> = discrepancies
+            .iter()
+            .map(|d| match d {
+                Discrepancy::Han { logged, scored } => {
+                    json!({ "field": "han", "logged": logged, "scored": scored })
+                }
+                Discrepancy::Fu { logged, scored } => {
+                    json!({ "field": "fu", "logged": logged, "scored": scored })
+                }
+                Discrepancy::Points { logged, scored } => {
+                    json!({ "field": "points", "logged": logged, "scored": scored })
+                }
+                Discrepancy::Error(e) => json!({ "field": "error", "error": json_error(e) }),
+            })
+            .collect();
+        return json!({
+            "round": win.round,
+            "honba": win.honba,
+            "winner": win.winner,
+            "loser": win.loser,
+            "tiles": win.tiles.join(" "),
+            "win": win.win,
+            "discrepancies": discrepancies
+        })
+        .to_string();
+    }
+
+    let result = if discrepancies.is_empty() {
+        "ok".to_string()
+    } else {
+        discrepancies
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<String>>()
+            .join("; ")
+    };
+    format!(
+        "{} ({}, win {}): {}",
+        win_title(win),
+        win.tiles.join(" "),
+        win.win,
+        result
+    )
+}
+
+/// Name the round, winner and loser of a replayed win (ex: `East 1-0: seat 1 ron from seat 0`).
+fn win_title(win: &LoggedWin) -> String {
+    let how = match win.loser {
+        Some(loser) => format!("ron from seat {}", loser),
+        None => "tsumo".to_string(),
+    };
+    format!("{}-{}: seat {} {}", win.round, win.honba, win.winner, how)
+}
+
+/// Score and print every win of a Mahjong Soul record, like hands given on the command line.
+pub fn parse_majsoul_log(path: &str, args: &Args) -> Exit {
+    let Ok(contents) = read_batch(path) else {
+        return print_read_error(path, args.json);
+    };
+    let wins = match majsoul::parse(&contents) {
+        Ok(wins) => wins,
+        Err(e) => return printout(Err(e), args.json),
+    };
+
+    let mut failed = 0;
+    for win in &wins {
+        let result = parse_hand(&win_args(args, win));
+        if result.is_err() {
+            failed += 1;
         }
+        match result {
+            Ok(o) if args.json => {
+                let result: serde_json::Value = serde_json::from_str(&o).unwrap();
+                println!(
+                    "{}",
+                    json!({ "round": win.round, "honba": win.honba, "winner": win.winner, "loser": win.loser, "result": result })
+                );
+            }
+            Ok(o) => println!("{}\n{}\n", win_title(win), o),
+            Err(e) if args.json => println!(
+                "{}",
+                json!({ "round": win.round, "honba": win.honba, "winner": win.winner, "loser": win.loser, "error": json_error(&e) })
+            ),
+            Err(e) => {
+                eprintln!("{}", win_title(win));
+                print_error(&e);
+            }
+        }
+    }
+    if failed == 0 {
+        Exit::Success
+    } else {
+        Exit::PartialBatchFailure
     }
 }
 
-fn main() {
-    let args = Args::parse();
-    if args.file.is_some() {
-        parse_file(&args);
-    } else if args.manual.is_some() {
-        let result = parse_calculator(&args);
-        printout(result);
+/// Get the arguments that score a replayed win, keeping the output options of `args`.
+fn win_args(args: &Args, win: &LoggedWin) -> Args {
+    let mut win_args = args.clone();
+    win_args.tiles = Some(win.tiles.clone());
+    win_args.win = Some(win.win.clone());
+    win_args.notation = Notation::Mpsz;
+    win_args.indicators = None;
+    win_args.dora = win.dora;
+    win_args.seat = win.seat.clone();
+    win_args.prev = win.prev.clone();
+    win_args.tsumo = win.is_tsumo();
+    win_args.riichi = win.riichi;
+    win_args.doubleriichi = win.doubleriichi;
+    win_args.ippatsu = win.ippatsu;
+    win_args.haitei = win.haitei;
+    win_args.rinshan = win.rinshan;
+    win_args.chankan = win.chankan;
+    win_args.tenhou = win.tenhou;
+    win_args.ba = win.honba;
+    win_args.manual = None;
+    win_args.sanma = None;
+    win_args.kita = 0;
+    win_args.majsoul_log = None;
+    win_args
+}
+
+/// Get the final standings of the `standings` command.
+pub fn parse_standings(
+    scores: &[i32],
+    uma: &[i32],
+    starting: i32,
+    returning: i32,
+    json: bool,
+) -> String {
+    let rules = StandingsRules {
+        uma: uma.try_into().unwrap(),
+        starting_points: starting,
+        returning_points: returning,
+    };
+    let standings = standings::standings(scores.try_into().unwrap(), &rules);
+    let total = |points: i32| points as f64 / 1000.0;
+
+    if json {
+        let standings: Vec<serde_json::Value> = standings
+            .iter()
+            .map(|s| json!({ "seat": s.seat, "rank": s.rank, "score": s.score, "points": total(s.points) }))
+            .collect();
+        return json!({ "standings": standings }).to_string();
+    }
+    standings
+        .iter()
+        .map(|s| {
+            format!(
+                "{}. Seat {}: {} ({:+.1})",
+                s.rank,
+                s.seat,
+                s.score,
+                total(s.points)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Run a single command, printing its result.
+pub fn run(args: &Args) -> Exit {
+    #[cfg(feature = "serve")]
+    if let Some(Command::Serve { port }) = &args.command {
+        return match serve::run(*port) {
+            Ok(()) => Exit::Success,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                Exit::IoError
+            }
+        };
+    }
+    #[cfg(feature = "tui")]
+    if let Some(Command::Tui) = &args.command {
+        return match tui::run() {
+            Ok(()) => Exit::Success,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                Exit::IoError
+            }
+        };
+    }
+    if let Some(Command::Standings {
+        scores,
+        uma,
+        starting,
+        returning,
+    }) = &args.command
+    {
+        println!(
+            "{}",
+            parse_standings(scores, uma, *starting, *returning, args.json)
+        );
+        Exit::Success
+    } else if let Some(Command::Repl { length, load }) = &args.command {
+        run_repl(*length, load.as_deref())
+    } else if let Some(path) = &args.csv {
+        parse_table(path, b',', args.json)
+    } else if let Some(path) = &args.tsv {
+        parse_table(path, b'\t', args.json)
+    } else if let Some(path) = &args.tenhou_log {
+        parse_tenhou_log(path, args.json)
+    } else if let Some(path) = &args.majsoul_log {
+        parse_majsoul_log(path, args)
+    } else if args.file.is_some() {
+        parse_file(args)
     } else {
-        let result = parse_hand(&args);
-        printout(result);
+        printout(evaluate(args), args.json)
+    }
+}
+
+fn json_failure(
+    code: &str,
+    category: &str,
+    message: &str,
+    input: serde_json::Value,
+) -> serde_json::Value {
+    json!({
+        "code" : code,
+        "category" : category,
+        "message" : message,
+        "input" : input
+    })
+}
+
+fn json_error(e: &HandErr) -> serde_json::Value {
+    let input = match e.input() {
+        Some(input) => json!({
+            "text" : input.input,
+            "group" : input.index.map(|i| i + 1),
+            "offset" : input.offset,
+            "length" : input.len,
+            "expected" : input.expected,
+            "found" : input.found
+        }),
+        None => json!(null),
+    };
+    json_failure(e.code(), e.category().code(), &e.to_string(), input)
+}
+
+pub fn json_error_out(e: &HandErr) -> String {
+    json!({ "error": json_error(e) }).to_string()
+}
+
+fn print_error(e: &HandErr) {
+    eprintln!("Error: {}", e);
+    if let Some(input) = e.input() {
+        for line in input.underline().lines() {
+            eprintln!("  {}", line);
+        }
+    }
+}
+
+/// Print the result, to stdout as json when `json` is set.
+pub fn printout(result: Result<String, HandErr>, json: bool) -> Exit {
+    match result {
+        Ok(o) => {
+            println!("{}", o);
+            Exit::Success
+        }
+        Err(e) if json => {
+            println!("{}", json_error_out(&e));
+            Exit::from(&e)
+        }
+        Err(e) => {
+            print_error(&e);
+            Exit::from(&e)
+        }
+    }
+}
+
+/// Commands of the scorekeeping repl.
+const REPL_HELP: &str = "\
+riichi <seat>                 declare riichi this round
+ron <winner> <loser> <hand>   win off a discard, with several rons separated by `|`
+tsumo <winner> <hand>         win by self draw
+draw [tenpai seats...]        exhaustive draw
+abort                         abortive draw
+undo                          take back the last command
+save <path>, load <path>      save or load the session as json
+scores, help, quit
+
+<hand> is given like on the command line (ex: --tiles 123m 456p 789s EEEw 55p -w 5p -d 1), or as -m <han> <fu>.
+the seat and round winds, tsumo and riichi are filled in from the game.";
+
+/// Keep score of a game, reading commands from stdin and printing the score table after each one.
+pub fn run_repl(length: Length, load: Option<&str>) -> Exit {
+    let mut session = match load {
+        Some(path) => match load_session(path) {
+            Ok(session) => session,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return Exit::IoError;
+            }
+        },
+        None => Session::new(length),
+    };
+    println!("{}", session.table());
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    loop {
+        if interactive {
+            print!("> ");
+            let _ = std::io::stdout().flush();
+        }
+        let mut line = String::new();
+        if !matches!(stdin.read_line(&mut line), Ok(1..)) {
+            break;
+        }
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let result = match command {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => {
+                println!("{}", REPL_HELP);
+                continue;
+            }
+            "scores" => Ok(()),
+            "undo" => session.undo(),
+            "save" => fs::write(rest.trim(), session.to_json().to_string())
+                .map_err(|e| format!("Unable to write file {}: {}", rest.trim(), e)),
+            "load" => load_session(rest.trim()).map(|loaded| session = loaded),
+            _ => session.apply(line),
+        };
+        match result {
+            Ok(()) => println!("{}", session.table()),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    Exit::Success
+}
+
+fn load_session(path: &str) -> Result<Session, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Unable to read file {}: {}", path, e))?;
+    Session::from_json(&contents)
+}
+
+/// A scorekeeping session, kept as the commands that changed the game so they can be undone, saved and loaded.
+#[derive(Debug, Clone)]
+struct Session {
+    length: Length,
+    game: Game,
+    /// Seats that declared riichi this round.
+    riichi: [bool; PLAYER_COUNT],
+    commands: Vec<String>,
+}
+
+impl Session {
+    fn new(length: Length) -> Self {
+        Self {
+            length,
+            game: Game::new(GameRules {
+                length: length.into(),
+                ..Default::default()
+            }),
+            riichi: [false; PLAYER_COUNT],
+            commands: Vec::new(),
+        }
+    }
+
+    /// Run a command that changes the game, recording it.
+    fn apply(&mut self, command: &str) -> Result<(), String> {
+        if self.game.finished {
+            return Err(HandErr::GameOver.to_string());
+        }
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            ["riichi", seat] => {
+                let seat = parse_seat(seat)?;
+                if self.riichi[seat] {
+                    return Err(format!("Seat {} already declared riichi", seat));
+                }
+                self.riichi[seat] = true;
+            }
+            ["ron" | "tsumo", ..] => {
+                let wins = command
+                    .split('|')
+                    .map(|win| self.win(win))
+                    .collect::<Result<Vec<Win>, String>>()?;
+                self.end_round(RoundEnd::Win(wins))?;
+            }
+            ["draw", tenpai @ ..] => {
+                let mut seats = [false; PLAYER_COUNT];
+                for seat in tenpai {
+                    seats[parse_seat(seat)?] = true;
+                }
+                self.end_round(RoundEnd::Draw { tenpai: seats })?;
+            }
+            ["abort"] => self.end_round(RoundEnd::AbortiveDraw)?,
+            _ => return Err(format!("Unknown command `{}`, try `help`", command)),
+        }
+        self.commands.push(command.to_string());
+        Ok(())
+    }
+
+    fn end_round(&mut self, end: RoundEnd) -> Result<(), String> {
+        self.game
+            .end_round(&end, self.riichi)
+            .map_err(|e| e.to_string())?;
+        self.riichi = [false; PLAYER_COUNT];
+        Ok(())
+    }
+
+    /// Score a win written as `ron <winner> <loser> <hand>` or `tsumo <winner> <hand>`.
+    fn win(&self, command: &str) -> Result<Win, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let (winner, loser, hand) = match words.as_slice() {
+            ["ron", winner, loser, hand @ ..] => {
+                (parse_seat(winner)?, Some(parse_seat(loser)?), hand)
+            }
+            ["tsumo", winner, hand @ ..] => (parse_seat(winner)?, None, hand),
+            _ => return Err(format!("Invalid win `{}`, try `help`", command.trim())),
+        };
+
+        let mut args = Args::try_parse_from(std::iter::once("mahc").chain(hand.iter().copied()))
+            .map_err(|e| usage_message(&e))?;
+        args.seat = self
+            .game
+            .round
+            .seat_wind(winner)
+            .map_err(|e| e.to_string())?
+            .to_string();
+        args.prev = self.game.round.wind_tile().to_string();
+        args.tsumo = loser.is_none();
+        args.riichi = self.riichi[winner] && !args.doubleriichi;
+        args.ba = 0;
+        let payment = match &args.manual {
+            Some(hanandfu) => calc::calculate(hanandfu, 0),
+            None => score_any_hand(&args).map(|(_, result)| result.0),
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(Win {
+            winner,
+            loser,
+            payment,
+            liable: None,
+        })
+    }
+
+    /// Take back the last command, replaying the ones before it.
+    fn undo(&mut self) -> Result<(), String> {
+        let mut commands = self.commands.clone();
+        if commands.pop().is_none() {
+            return Err("Nothing to undo".to_string());
+        }
+        *self = Self::replay(self.length, &commands)?;
+        Ok(())
+    }
+
+    fn replay(length: Length, commands: &[String]) -> Result<Self, String> {
+        let mut session = Self::new(length);
+        for command in commands {
+            session
+                .apply(command)
+                .map_err(|e| format!("`{}`: {}", command, e))?;
+        }
+        Ok(session)
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let length = self.length.to_possible_value().unwrap();
+        json!({ "length": length.get_name(), "commands": self.commands })
+    }
+
+    fn from_json(contents: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(contents).map_err(|e| format!("Invalid session: {}", e))?;
+        let length = value["length"]
+            .as_str()
+            .and_then(|length| Length::from_str(length, true).ok())
+            .ok_or("Invalid session: missing the game `length`")?;
+        let commands: Vec<String> = value["commands"]
+            .as_array()
+            .ok_or("Invalid session: missing the `commands`")?
+            .iter()
+            .map(|command| command.as_str().map(String::from))
+            .collect::<Option<_>>()
+            .ok_or("Invalid session: commands must be strings")?;
+        Self::replay(length, &commands)
+    }
+
+    /// Show the round, riichi sticks and scores, and the final standings once the game is over.
+    fn table(&self) -> String {
+        let game = &self.game;
+        let mut out = format!("{} | riichi sticks: {}", game.round, game.riichi_sticks);
+        for seat in 0..PLAYER_COUNT {
+            out.push_str(&format!(
+                "\n  Seat {} ({}): {}{}",
+                seat,
+                game.round.seat_wind(seat).unwrap(),
+                game.scores[seat],
+                if self.riichi[seat] { " riichi" } else { "" }
+            ));
+        }
+        if game.finished {
+            let rules = StandingsRules {
+                starting_points: game.rules.starting_points,
+                ..Default::default()
+            };
+            out.push_str("\nGame over\n");
+            out.push_str(&parse_standings(
+                &game.scores,
+                &rules.uma,
+                rules.starting_points,
+                rules.returning_points,
+                false,
+            ));
+        }
+        out
+    }
+}
+
+/// Read a seat index (0-3).
+fn parse_seat(seat: &str) -> Result<usize, String> {
+    seat.parse()
+        .ok()
+        .filter(|&seat| seat < PLAYER_COUNT)
+        .ok_or_else(|| format!("Invalid seat `{}`, seats are 0 to 3", seat))
+}
+
+fn main() -> ExitCode {
+    let mut args = Args::parse();
+    if args.file.is_none()
+        && args.csv.is_none()
+        && args.tsv.is_none()
+        && args.tenhou_log.is_none()
+        && args.majsoul_log.is_none()
+        && args.command.is_none()
+        && args.tiles.is_none()
+        && args.manual.is_none()
+        && !std::io::stdin().is_terminal()
+    {
+        args.file = Some(STDIN_PATH.to_string());
     }
+    ExitCode::from(run(&args) as u8)
 }
 
 #[cfg(test)]
@@ -446,4 +1756,451 @@ mod test {
                 .to_string())
         );
     }
+    #[test]
+    fn sanma_calc() {
+        let args = Args::parse_from(["", "--manual", "4", "30", "--sanma"]);
+        let out = parse_calculator(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n4 Han/ 30 Fu\nDealer: 11600 (3900)\nnon-dealer: 7700 (2000/3900)".to_string())
+        );
+        let args = Args::parse_from([
+            "",
+            "--manual",
+            "4",
+            "30",
+            "--sanma",
+            "north-bisection",
+            "--ba",
+            "1",
+        ]);
+        let out = parse_calculator(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n4 Han/ 30 Fu/ 1 Honba\nDealer: 11900 (6000)\nnon-dealer: 8000 (3100/5000)"
+                .to_string())
+        );
+    }
+    #[test]
+    fn sanma_mangan_calc() {
+        let args = Args::parse_from(["", "--manual", "5", "30", "--sanma", "north-bisection"]);
+        let out = parse_calculator(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n5 Han/ 30 Fu\nDealer: 12000 (6000)\nnon-dealer: 8000 (3000/5000)".to_string())
+        );
+    }
+    #[test]
+    fn sanma_hand() {
+        let args = Args::parse_from([
+            "", "--tiles", "111m", "999p", "123s", "EEEw", "55s", "-w", "5s", "--sanma", "--kita",
+            "2", "--json",
+        ]);
+        let out: serde_json::Value = serde_json::from_str(&parse_hand(&args).unwrap()).unwrap();
+        assert_eq!(out["han"], 6);
+        assert_eq!(out["kita"], 2);
+
+        let args = Args::parse_from([
+            "", "--tiles", "111m", "999p", "123so", "EEEw", "55s", "-w", "5s", "--sanma",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::SanmaChii);
+
+        let args = Args::parse_from([
+            "", "--tiles", "111m", "999p", "123s", "EEEw", "55s", "-w", "5s", "--kita", "1",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::KitaWithoutSanma);
+    }
+    #[test]
+    fn too_many_copies_with_indicators() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "123m",
+            "456p",
+            "789s",
+            "EEEw",
+            "11p",
+            "-w",
+            "1p",
+            "--indicators",
+            "Ew",
+            "Sw",
+        ]);
+        assert!(parse_hand(&args).is_ok());
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "123m",
+            "456p",
+            "789s",
+            "EEEw",
+            "11p",
+            "-w",
+            "1p",
+            "--indicators",
+            "Ew",
+            "Ew",
+        ]);
+        assert_eq!(
+            parse_hand(&args).unwrap_err(),
+            HandErr::TooManyCopies {
+                tile: "Ew".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn json_error() {
+        let args = Args::parse_from([
+            "", "--tiles", "123m", "1z3m", "EEEw", "55s", "-w", "5s", "--json",
+        ]);
+        let out: serde_json::Value =
+            serde_json::from_str(&json_error_out(&parse_hand(&args).unwrap_err())).unwrap();
+        assert_eq!(out["error"]["code"], "invalid_group");
+        assert_eq!(out["error"]["category"], "parse");
+        assert_eq!(out["error"]["input"]["text"], "1z3m");
+        assert_eq!(out["error"]["input"]["group"], 2);
+        assert_eq!(out["error"]["input"]["offset"], 1);
+
+        let out: serde_json::Value =
+            serde_json::from_str(&json_error_out(&HandErr::NoYaku)).unwrap();
+        assert_eq!(out["error"]["code"], "no_yaku");
+        assert_eq!(out["error"]["message"], "No Yaku");
+        assert!(out["error"]["input"].is_null());
+    }
+
+    #[test]
+    fn exit_codes() {
+        let args = Args::parse_from(["", "--tiles", "123m", "1z3m", "EEEw", "55s", "-w", "5s"]);
+        assert_eq!(
+            Exit::from(&parse_hand(&args).unwrap_err()),
+            Exit::ParseError
+        );
+        let args = Args::parse_from(["", "--tiles", "123m", "EEEw", "55s", "-w", "5s"]);
+        assert_eq!(
+            Exit::from(&parse_hand(&args).unwrap_err()),
+            Exit::InvalidShape
+        );
+        let args = Args::parse_from([
+            "", "--tiles", "123m", "456p", "789s", "234mo", "11p", "-w", "1p",
+        ]);
+        assert_eq!(Exit::from(&parse_hand(&args).unwrap_err()), Exit::NoYaku);
+        let args = Args::parse_from(["", "-f", "/nonexistent/hands.txt"]);
+        assert_eq!(run(&args), Exit::IoError);
+        let args = Args::parse_from(["", "--csv", "/nonexistent/hands.csv", "--json"]);
+        assert_eq!(run(&args), Exit::IoError);
+        let args = Args::parse_from(["", "--tenhou-log", "/nonexistent/log.json", "--json"]);
+        assert_eq!(run(&args), Exit::IoError);
+        let args = Args::parse_from(["", "--majsoul-log", "/nonexistent/paipu.json", "--json"]);
+        assert_eq!(run(&args), Exit::IoError);
+    }
+
+    #[test]
+    fn batch_skips_comments_and_blank_lines() {
+        let contents = "# scores\n-m 4 30\n\n  # indented comment\n  -m 1 30  \n";
+        assert_eq!(batch_lines(contents), vec![(2, "-m 4 30"), (5, "-m 1 30")]);
+    }
+
+    #[test]
+    fn batch_continues_past_bad_lines() {
+        let path = std::env::temp_dir().join("mahc_batch_continues_past_bad_lines.txt");
+        fs::write(&path, "--not-an-arg\n-m 0 30\n-m 4 30\n").unwrap();
+        let args = Args::parse_from(["", "-f", path.to_str().unwrap()]);
+        let mut summary = BatchSummary::default();
+        assert_eq!(
+            run_file(&args, &mut summary, &mut Vec::new()),
+            Exit::PartialBatchFailure
+        );
+        assert_eq!(
+            summary,
+            BatchSummary {
+                succeeded: 1,
+                failed: 2
+            }
+        );
+
+        let args = Args::parse_from(["", "-f", path.to_str().unwrap(), "--fail-fast"]);
+        assert_eq!(run(&args), Exit::Usage);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn batch_include_cycle() {
+        let dir = std::env::temp_dir().join("mahc_batch_include_cycle");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.txt"), "-m 4 30\n-f nested/b.txt\n").unwrap();
+        fs::write(dir.join("nested").join("b.txt"), "-m 1 30\n-f ../a.txt\n").unwrap();
+        let args = Args::parse_from(["", "-f", dir.join("a.txt").to_str().unwrap()]);
+        let mut summary = BatchSummary::default();
+        assert_eq!(
+            run_file(&args, &mut summary, &mut Vec::new()),
+            Exit::PartialBatchFailure
+        );
+        assert_eq!(
+            summary,
+            BatchSummary {
+                succeeded: 2,
+                failed: 1
+            }
+        );
+        let args = Args::parse_from(["", "-f", dir.join("a.txt").to_str().unwrap(), "--fail-fast"]);
+        assert_eq!(run(&args), Exit::InvalidInclude);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn batch_include_depth() {
+        let includes = vec![PathBuf::from("/"); MAX_INCLUDE_DEPTH];
+        assert_eq!(
+            check_include("hands.txt", &includes).unwrap_err().0,
+            "include_depth"
+        );
+        assert_eq!(
+            resolve_include("dir/a.txt", "b.txt"),
+            Path::new("dir").join("b.txt").to_string_lossy()
+        );
+        assert_eq!(resolve_include("-", "b.txt"), "b.txt");
+    }
+
+    #[test]
+    fn table_rows() {
+        let contents = "player,tiles,win,seat,prev,riichi,dora\n\
+            A,123p 456p 789p rrrdo 99p,9p,Ew,Ew,,2\n\
+            B,123m 1z3m EEEw 55s,5s,Ew,Ew,,0\n\
+            C,111m 999p 111s EEEw 99s,9s,Sw,Ew,yes,0\n";
+        let mut out = Vec::new();
+        assert_eq!(score_table(contents, b',', &mut out).unwrap(), 1);
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "player,tiles,win,seat,prev,riichi,dora,han,fu,yaku,limit,dealer_ron,dealer_tsumo,\
+            non_dealer_ron,non_dealer_tsumo_non_dealer,non_dealer_tsumo_dealer,error"
+        );
+        assert_eq!(
+            lines[1],
+            "A,123p 456p 789p rrrdo 99p,9p,Ew,Ew,,2,6,30,Honitsu: 2; Ittsuu: 1; Yakuhai: 1,\
+            Haneman,18000,6000,12000,3000,6000,"
+        );
+        assert!(lines[2].starts_with("B,123m 1z3m EEEw 55s,5s,Ew,Ew,,0,,,,,,,,,,"));
+        assert!(lines[2].ends_with("\"Invalid Group found in group 2: expected a tile value (1-9, E, S, W, N, r, g, w), found `z`\""));
+        assert!(lines[3].contains("Yakuman"));
+
+        // rows are checked and read in any notation like --tiles
+        let contents = "tiles,win,tsumo,chankan,riichi\n\
+            123m 456p 789s EEEw 22m,2m,1,1,\n\
+            123m456p789s11222z,,,,1\n";
+        let mut out = Vec::new();
+        assert_eq!(score_table(contents, b',', &mut out).unwrap(), 1);
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(
+            lines[1].ends_with(",Cant Tsumo and Chankan"),
+            "{}",
+            lines[1]
+        );
+        assert!(lines[2].ends_with(",1,40,Riichi: 1,,2000,700,1300,400,700,"));
+    }
+
+    #[test]
+    fn mpsz_hand() {
+        let args = Args::parse_from(["", "--tiles", "234m0p67p55s", "c789s", "p777z", "-d", "1"]);
+        let mahc = Args::parse_from([
+            "", "--tiles", "789soL", "rrrdo", "234m", "567p", "55s", "-w", "5s", "-d", "2",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap(), parse_hand(&mahc).unwrap());
+
+        // read as 123m 123m 123m rather than 111m 222m 333m for the higher score
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "111222333m789s55z",
+            "--notation",
+            "mpsz",
+            "-w",
+            "5z",
+            "-s",
+            "2z",
+            "-t",
+        ]);
+        let mahc = Args::parse_from([
+            "", "--tiles", "123m", "123m", "123m", "789s", "wwd", "-w", "wd", "-s", "Sw", "-t",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap(), parse_hand(&mahc).unwrap());
+    }
+
+    #[test]
+    fn majsoul_win() {
+        let record = r#"{"actions": [
+            {"name": "RecordNewRound", "data": {"chang": 1, "ju": 3, "ben": 2}},
+            {"name": "RecordDiscardTile", "data": {"seat": 1, "tile": "7z"}},
+            {"name": "RecordHule", "data": {"hules": [{
+                "seat": 2, "zimo": false, "hand": ["2m", "3m", "4m", "0p", "6p", "7p", "5s", "5s", "7z", "7z"],
+                "ming": ["shunzi(7s,8s,9s)"], "hu_tile": "7z", "doras": ["1z"], "fans": [], "point_rong": 2000
+            }]}}
+        ]}"#;
+        let win = &majsoul::parse(record).unwrap()[0];
+        // the flags of the command line are replaced by the ones of the win
+        let args = Args::parse_from(["", "--json", "-r"]);
+        let mahc = Args::parse_from([
+            "", "--tiles", "789soL", "234m", "567p", "55s", "rrrd", "-w", "rd", "-s", "Nw", "-p",
+            "Sw", "-d", "1", "-b", "2", "--json",
+        ]);
+        assert_eq!(
+            parse_hand(&win_args(&args, win)).unwrap(),
+            parse_hand(&mahc).unwrap()
+        );
+    }
+
+    #[test]
+    fn standings_command() {
+        let args = Args::parse_from(["", "standings", "32000", "28000", "24500", "15500"]);
+        let Some(Command::Standings {
+            scores,
+            uma,
+            starting,
+            returning,
+        }) = &args.command
+        else {
+            panic!("expected the standings command");
+        };
+        assert_eq!(
+            parse_standings(scores, uma, *starting, *returning, false),
+            "1. Seat 0: 32000 (+37.0)\n2. Seat 1: 28000 (+3.0)\n3. Seat 2: 24500 (-10.5)\n4. Seat 3: 15500 (-29.5)"
+        );
+
+        let args =
+            Args::try_parse_from(["", "standings", "0", "1", "2", "3", "--uma", "10", "-10"]);
+        assert!(args.is_err());
+
+        let args = Args::try_parse_from([
+            "",
+            "standings",
+            "30000",
+            "25000",
+            "25000",
+            "20000",
+            "--uma",
+            "30",
+            "10",
+            "-10",
+            "-30",
+            "--uma",
+            "1",
+            "2",
+            "3",
+            "4",
+        ]);
+        assert_eq!(
+            args.unwrap_err().kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+
+        let args = Args::try_parse_from([
+            "",
+            "standings",
+            "0",
+            "1",
+            "2",
+            "3",
+            "--uma",
+            "1",
+            "2",
+            "3",
+            "4 5",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn repl_session() {
+        let mut session = Session::new(Length::South);
+        session.apply("riichi 1").unwrap();
+        session
+            .apply("ron 1 0 --tiles 123456789m23455p -w 5p")
+            .unwrap();
+        assert_eq!(session.game.scores, [19800, 30200, 25000, 25000]);
+        session.apply("ron 2 0 -m 3 30 | ron 3 0 -m 1 30").unwrap();
+        assert_eq!(session.game.scores, [14900, 30200, 28900, 26000]);
+
+        session.undo().unwrap();
+        assert_eq!(session.game.scores, [19800, 30200, 25000, 25000]);
+        assert_eq!(session.game.round.to_string(), "East 2-0");
+
+        assert!(session.apply("ron 1 1 -m 1 30").is_err());
+        assert!(session.apply("tsumo 4 -m 1 30").is_err());
+        assert!(session.apply("pass").is_err());
+        assert_eq!(session.commands.len(), 2);
+
+        let loaded = Session::from_json(&session.to_json().to_string()).unwrap();
+        assert_eq!(loaded.game, session.game);
+        assert!(
+            Session::from_json(r#"{"length": "south", "commands": ["abort", "win"]}"#).is_err()
+        );
+
+        let mut session = Session::new(Length::East);
+        assert_eq!(session.undo(), Err("Nothing to undo".to_string()));
+    }
+
+    #[test]
+    fn unicode_tiles() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "🀈🀉🀊🀕🀖🀗🀔🀔",
+            "p🀄🀄🀄",
+            "a🀙🀙🀙🀙",
+            "-w",
+            "🀔",
+            "--unicode",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.starts_with("\n🀈🀉🀊🀕🀖🀗🀔🀔 🀄🀄🀄 🀫🀙🀙🀫\n"));
+        let mahc = Args::parse_from([
+            "", "--tiles", "rrrdo", "1111p", "234m", "678s", "55s", "-w", "5s",
+        ]);
+        assert!(out.ends_with(&parse_hand(&mahc).unwrap()));
+    }
+
+    #[test]
+    fn chankan_robbed_kan() {
+        let hand = [
+            "",
+            "--tiles",
+            "123m",
+            "456p",
+            "789s",
+            "EEEw",
+            "11p",
+            "-w",
+            "1p",
+            "--chankan",
+        ];
+        assert!(parse_hand(&Args::parse_from(hand)).is_ok());
+        let args = Args::parse_from(hand.iter().chain(&["--robbed-kan", "ankan"]));
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::ChankanInvalidKan);
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "1m",
+            "9m",
+            "1p",
+            "9p",
+            "1s",
+            "9s",
+            "Ew",
+            "Sw",
+            "Ww",
+            "Nw",
+            "gd",
+            "rd",
+            "wwd",
+            "-w",
+            "wd",
+            "--chankan",
+            "--robbed-kan",
+            "ankan",
+        ]);
+        assert!(parse_hand(&args).is_ok());
+    }
 }
@@ -0,0 +1,717 @@
+use crate::hand::error::{HandErr, InputErr};
+
+/// Number of different tiles (9 of each number suit, 4 winds and 3 dragons).
+const TILE_KINDS: usize = 34;
+
+/// The face down tile of the Unicode mahjong tiles block.
+pub const UNICODE_BACK: char = '\u{1F02B}';
+
+/// Offsets of each tile from the start of the Unicode mahjong tiles block (U+1F000), by tile index.
+const UNICODE_OFFSETS: [u32; TILE_KINDS] = [
+    0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, // manzu
+    0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F, 0x20, 0x21, // pinzu
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, // souzu
+    0x00, 0x01, 0x02, 0x03, // winds
+    0x06, 0x05, 0x04, // white, green and red dragons
+];
+
+/// A hand written in the compact MPSZ notation used by Tenhou and Mahjong Soul (ex: `123m456p789s11z`).
+///
+/// Numbers are followed by their suit: `m`, `p` and `s` for the number suits, and `z` for honors
+/// (`1z` to `4z` for East, South, West and North, `5z` for white, `6z` for green and `7z` for red dragon).
+/// `0` is a red five.
+///
+/// Called melds are written as separate groups with a call marker in front:
+/// `c` for chi (ex: `c345m`), `p` for pon (ex: `p555z`), `k` for an open kan (ex: `k1111s`),
+/// `s` for an added kan (ex: `s5555m`) and `a` for a closed kan (ex: `a9999p`).
+/// The concealed tiles include the winning tile, which is the last tile unless given otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MpszHand {
+    /// Concealed tiles, as tile indices.
+    closed: Vec<usize>,
+    /// Called melds, in the hand notation (ex: `555po`).
+    calls: Vec<String>,
+    /// Winning tile, as a tile index.
+    win: usize,
+    red_fives: u16,
+}
+
+impl MpszHand {
+    /// Parse a hand written in the MPSZ notation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::notation::MpszHand;
+    ///
+    /// let hand = MpszHand::parse("234m0p67p11z c789s p777z").unwrap();
+    ///
+    /// assert_eq!(hand.win(), "Ew");
+    /// assert_eq!(hand.calls(), ["789soL", "rrrdo"]);
+    /// assert_eq!(hand.red_fives(), 1);
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, HandErr> {
+        let mut closed = Vec::new();
+        let mut calls = Vec::new();
+        let mut red_fives = 0;
+
+        let mut offset = 0;
+        for token in input.split(' ') {
+            if !token.is_empty() {
+                let (tiles, reds) = match token.chars().next() {
+                    Some(marker @ ('c' | 'p' | 'k' | 's' | 'a')) => {
+                        let (tiles, reds) = parse_tiles(input, offset + 1, &token[1..])?;
+                        calls.push(call(input, offset, token, marker, &tiles)?);
+                        (Vec::new(), reds)
+                    }
+                    _ => parse_tiles(input, offset, token)?,
+                };
+                closed.extend(tiles);
+                red_fives += reds;
+            }
+            offset += token.chars().count() + 1;
+        }
+
+        let Some(&win) = closed.last() else {
+            return Err(HandErr::NoHandTiles);
+        };
+
+        let tile_count = closed.len() + calls.len() * 3;
+        if tile_count != 14 {
+            return Err(HandErr::InvalidShape(InputErr::whole(
+                input,
+                "14 tiles, counting each call as 3",
+                &format!("{} tiles", tile_count),
+            )));
+        }
+
+        Ok(Self {
+            closed,
+            calls,
+            win,
+            red_fives,
+        })
+    }
+
+    /// Set the winning tile, written in either the MPSZ or the hand notation. It must be one of the concealed tiles.
+    pub fn with_win(mut self, tile: &str) -> Result<Self, HandErr> {
+        let win = tile_index(tile)?;
+        if !self.closed.contains(&win) {
+            return Err(HandErr::InvalidShape(InputErr::whole(
+                tile,
+                "the winning tile to be one of the concealed tiles",
+                &format!("`{}`", tile),
+            )));
+        }
+        self.win = win;
+        Ok(self)
+    }
+
+    /// Get the winning tile in the hand notation.
+    pub fn win(&self) -> String {
+        tile_string(self.win)
+    }
+
+    /// Get the called melds in the hand notation.
+    pub fn calls(&self) -> &[String] {
+        &self.calls
+    }
+
+    /// Get the number of red fives, which each count as a dora.
+    pub fn red_fives(&self) -> u16 {
+        self.red_fives
+    }
+
+    /// Get every way to split the hand into groups, written in the hand notation with the group completed by the winning tile last.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::notation::MpszHand;
+    ///
+    /// let hand = MpszHand::parse("111222333m789s55z").unwrap();
+    ///
+    /// assert_eq!(
+    ///     hand.arrangements().unwrap(),
+    ///     vec![
+    ///         vec!["111m", "222m", "333m", "789s", "wwd"],
+    ///         vec!["123m", "123m", "123m", "789s", "wwd"],
+    ///     ]
+    /// );
+    /// ```
+    pub fn arrangements(&self) -> Result<Vec<Vec<String>>, HandErr> {
+        let mut counts = [0u8; TILE_KINDS];
+        for &tile in &self.closed {
+            counts[tile] += 1;
+        }
+
+        let mut decompositions: Vec<Vec<Vec<usize>>> = Vec::new();
+        for pair in 0..TILE_KINDS {
+            if counts[pair] < 2 {
+                continue;
+            }
+            counts[pair] -= 2;
+            let mut sets = Vec::new();
+            let mut found = Vec::new();
+            decompose(&mut counts, &mut sets, &mut found);
+            counts[pair] += 2;
+            for mut groups in found {
+                groups.push(vec![pair, pair]);
+                decompositions.push(groups);
+            }
+        }
+
+        if self.calls.is_empty() {
+            if counts.iter().filter(|&&count| count == 2).count() == 7 {
+                decompositions.push(
+                    (0..TILE_KINDS)
+                        .filter(|&tile| counts[tile] == 2)
+                        .map(|tile| vec![tile, tile])
+                        .collect(),
+                );
+            }
+            if let Some(kokushi) = kokushi(&counts) {
+                decompositions.push(kokushi);
+            }
+        }
+
+        let mut arrangements: Vec<Vec<String>> = Vec::new();
+        for groups in decompositions {
+            let mut tried: Vec<&Vec<usize>> = Vec::new();
+            for (index, last) in groups.iter().enumerate() {
+                if !last.contains(&self.win) || tried.contains(&last) {
+                    continue;
+                }
+                tried.push(last);
+
+                let mut arrangement = self.calls.clone();
+                arrangement.extend(
+                    groups
+                        .iter()
+                        .enumerate()
+                        .filter(|&(other, _)| other != index)
+                        .map(|(_, group)| group_string(group, "")),
+                );
+                arrangement.push(group_string(last, ""));
+                if !arrangements.contains(&arrangement) {
+                    arrangements.push(arrangement);
+                }
+            }
+        }
+
+        if arrangements.is_empty() {
+            let tiles: Vec<String> = self.closed.iter().map(|&tile| tile_string(tile)).collect();
+            return Err(HandErr::InvalidShape(InputErr::whole(
+                &tiles.join(" "),
+                "4 groups and a pair, 7 pairs, or 13 orphans",
+                "an incomplete hand",
+            )));
+        }
+
+        Ok(arrangements)
+    }
+}
+
+/// Get the tiles that complete a hand of 13 tiles written in the MPSZ notation, in the hand notation.
+///
+/// Tiles the hand already holds all 4 of are not counted as waits.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::notation::waits;
+///
+/// assert_eq!(waits("123m456p789m23s55z").unwrap(), ["1s", "4s"]);
+/// assert_eq!(waits("1112345678999m").unwrap().len(), 9);
+/// ```
+pub fn waits(input: &str) -> Result<Vec<String>, HandErr> {
+    let mut counts = [0usize; TILE_KINDS];
+    let mut tile_count = 0;
+    let mut offset = 0;
+    for token in input.split(' ') {
+        if !token.is_empty() {
+            let tiles = match token.chars().next() {
+                Some('c' | 'p' | 'k' | 's' | 'a') => {
+                    tile_count += 3;
+                    parse_tiles(input, offset + 1, &token[1..])?.0
+                }
+                _ => {
+                    let tiles = parse_tiles(input, offset, token)?.0;
+                    tile_count += tiles.len();
+                    tiles
+                }
+            };
+            for tile in tiles {
+                counts[tile] += 1;
+                if counts[tile] > 4 {
+                    return Err(HandErr::InvalidShape(InputErr::whole(
+                        input,
+                        "at most 4 copies of each tile",
+                        &format!("5 copies of {}", tile_string(tile)),
+                    )));
+                }
+            }
+        }
+        offset += token.chars().count() + 1;
+    }
+    if tile_count != 13 {
+        return Err(HandErr::InvalidShape(InputErr::whole(
+            input,
+            "13 tiles, counting each call as 3",
+            &format!("{} tiles", tile_count),
+        )));
+    }
+
+    let mut waits = Vec::new();
+    for tile in (0..TILE_KINDS).filter(|&tile| counts[tile] < 4) {
+        let hand = MpszHand::parse(&format!("{} {}", input, mpsz_string(tile)))?;
+        if hand.arrangements().is_ok() {
+            waits.push(tile_string(tile));
+        }
+    }
+    Ok(waits)
+}
+
+/// Check if the hand tiles are written in the MPSZ notation rather than the hand notation.
+///
+/// Every group must be made of MPSZ characters, and at least one can only be read as MPSZ (ex: `11z`, `406m`, `123m456p`, `p555s`).
+/// Groups that are valid in both notations (ex: `123m`) are read as the hand notation.
+pub fn is_mpsz(tiles: &[String]) -> bool {
+    let groups: Vec<(bool, &str)> = tiles
+        .iter()
+        .map(
+            |token| match token.strip_prefix(['c', 'p', 'k', 's', 'a']) {
+                Some(body) => (true, body),
+                None => (false, token.as_str()),
+            },
+        )
+        .collect();
+    let is_mpsz_group = |body: &str| {
+        !body.is_empty()
+            && body
+                .chars()
+                .all(|c| c.is_ascii_digit() || "mpsz".contains(c))
+    };
+    groups.iter().all(|(_, body)| is_mpsz_group(body))
+        && groups.iter().any(|&(called, body)| {
+            called
+                || body.contains(['z', '0'])
+                || body.chars().filter(|c| "mpsz".contains(*c)).count() > 1
+        })
+}
+
+/// Convert a single tile written in the MPSZ notation (ex: `5z`) to the hand notation (ex: `wd`).
+///
+/// Tiles already in the hand notation are returned as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::notation::to_hand_tile;
+///
+/// assert_eq!(to_hand_tile("1z").unwrap(), "Ew");
+/// assert_eq!(to_hand_tile("0p").unwrap(), "5p");
+/// assert_eq!(to_hand_tile("rd").unwrap(), "rd");
+/// ```
+pub fn to_hand_tile(tile: &str) -> Result<String, HandErr> {
+    tile_index(tile).map(tile_string)
+}
+
+/// Convert a single tile, written in the hand or MPSZ notation, to its Unicode mahjong tile (ex: `Ew` gives 🀀).
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::notation::to_unicode_tile;
+///
+/// assert_eq!(to_unicode_tile("1m").unwrap(), '🀇');
+/// assert_eq!(to_unicode_tile("rd").unwrap(), '🀄');
+/// ```
+pub fn to_unicode_tile(tile: &str) -> Result<char, HandErr> {
+    tile_index(tile).map(|index| char::from_u32(0x1F000 + UNICODE_OFFSETS[index]).unwrap())
+}
+
+/// Check if any of the hand tiles are written as Unicode mahjong tiles.
+pub fn is_unicode(tiles: &[String]) -> bool {
+    tiles
+        .iter()
+        .any(|token| token.chars().any(|c| unicode_index(c).is_some()))
+}
+
+/// Rewrite the Unicode mahjong tiles in a group as the MPSZ notation (ex: 🀇🀈🀉 gives `1m2m3m`), keeping any call marker.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::notation::from_unicode;
+///
+/// assert_eq!(from_unicode("p🀄🀄🀄"), "p7z7z7z");
+/// ```
+pub fn from_unicode(token: &str) -> String {
+    token
+        .chars()
+        .map(|c| match unicode_index(c) {
+            Some(index) => mpsz_string(index),
+            None => c.to_string(),
+        })
+        .collect()
+}
+
+/// Get the tile index of a Unicode mahjong tile.
+fn unicode_index(c: char) -> Option<usize> {
+    let offset = (c as u32).checked_sub(0x1F000)?;
+    UNICODE_OFFSETS.iter().position(|&o| o == offset)
+}
+
+/// Parse a single tile written in either notation, or as a Unicode mahjong tile, into its tile index.
+fn tile_index(tile: &str) -> Result<usize, HandErr> {
+    let chars: Vec<char> = tile.chars().collect();
+    if let [c] = chars[..] {
+        if let Some(index) = unicode_index(c) {
+            return Ok(index);
+        }
+    }
+    if let [value, suit] = chars[..] {
+        if let Some(index) = "ESWN".find(value).filter(|_| suit == 'w') {
+            return Ok(27 + index);
+        }
+        if let Some(index) = "wgr".find(value).filter(|_| suit == 'd') {
+            return Ok(31 + index);
+        }
+    }
+    match parse_tiles(tile, 0, tile)?.0[..] {
+        [index] => Ok(index),
+        _ => Err(HandErr::InvalidGroup(InputErr::whole(
+            tile,
+            "a single tile (ex: 5m, 1z, Ew)",
+            &format!("`{}`", tile),
+        ))),
+    }
+}
+
+/// Parse the tiles of a `token` starting at `offset` in the `input`, returning the tile indices and the number of red fives.
+fn parse_tiles(input: &str, offset: usize, token: &str) -> Result<(Vec<usize>, u16), HandErr> {
+    let mut tiles = Vec::new();
+    let mut red_fives = 0;
+    let mut values: Vec<(usize, u32)> = Vec::new();
+
+    for (position, c) in token.chars().enumerate() {
+        if let Some(value) = c.to_digit(10) {
+            values.push((position, value));
+            continue;
+        }
+        let base = match c {
+            'm' => 0,
+            'p' => 9,
+            's' => 18,
+            'z' => 27,
+            _ => {
+                return Err(HandErr::InvalidGroup(InputErr::new(
+                    input,
+                    offset + position,
+                    1,
+                    "a tile number (0-9) or suit (m, p, s, z)",
+                )))
+            }
+        };
+        if values.is_empty() {
+            return Err(HandErr::InvalidGroup(InputErr::new(
+                input,
+                offset + position,
+                1,
+                "tile numbers before the suit",
+            )));
+        }
+        for (value_position, value) in values.drain(..) {
+            let value = match (value, c) {
+                (0, 'z') | (8..=9, 'z') => {
+                    return Err(HandErr::InvalidGroup(InputErr::new(
+                        input,
+                        offset + value_position,
+                        1,
+                        "an honor number (1-7)",
+                    )))
+                }
+                (0, _) => {
+                    red_fives += 1;
+                    5
+                }
+                (value, _) => value,
+            };
+            tiles.push(base + value as usize - 1);
+        }
+    }
+
+    if let Some(&(position, _)) = values.first() {
+        return Err(HandErr::InvalidGroup(InputErr::new(
+            input,
+            offset + position,
+            values.len(),
+            "a suit (m, p, s, z) after the tile numbers",
+        )));
+    }
+
+    Ok((tiles, red_fives))
+}
+
+/// Convert the tiles of a called meld to the hand notation.
+fn call(
+    input: &str,
+    offset: usize,
+    token: &str,
+    marker: char,
+    tiles: &[usize],
+) -> Result<String, HandErr> {
+    let mut sorted = tiles.to_vec();
+    sorted.sort();
+    let same = sorted.windows(2).all(|pair| pair[0] == pair[1]);
+    let (valid, expected) = match marker {
+        'c' => (
+            sorted.len() == 3
+                && sorted[0] < 27
+                && sorted[0] % 9 <= 6
+                && sorted[1] == sorted[0] + 1
+                && sorted[2] == sorted[0] + 2,
+            "a chi of 3 number tiles in sequence",
+        ),
+        'p' => (sorted.len() == 3 && same, "a pon of 3 of the same tile"),
+        _ => (sorted.len() == 4 && same, "a kan of 4 of the same tile"),
+    };
+    let meld = match marker {
+        'c' => "oL",
+        's' => "o+",
+        'a' => "",
+        _ => "o",
+    };
+    if !valid {
+        return Err(HandErr::InvalidGroup(InputErr::new(
+            input,
+            offset,
+            token.chars().count(),
+            expected,
+        )));
+    }
+    Ok(group_string(&sorted, meld))
+}
+
+/// Find every way to split the tiles into triplets and sequences.
+fn decompose(
+    counts: &mut [u8; TILE_KINDS],
+    sets: &mut Vec<Vec<usize>>,
+    found: &mut Vec<Vec<Vec<usize>>>,
+) {
+    let Some(tile) = counts.iter().position(|&count| count > 0) else {
+        found.push(sets.clone());
+        return;
+    };
+
+    if counts[tile] >= 3 {
+        counts[tile] -= 3;
+        sets.push(vec![tile; 3]);
+        decompose(counts, sets, found);
+        sets.pop();
+        counts[tile] += 3;
+    }
+
+    if tile < 27 && tile % 9 <= 6 && counts[tile + 1] > 0 && counts[tile + 2] > 0 {
+        counts[tile..tile + 3]
+            .iter_mut()
+            .for_each(|count| *count -= 1);
+        sets.push(vec![tile, tile + 1, tile + 2]);
+        decompose(counts, sets, found);
+        sets.pop();
+        counts[tile..tile + 3]
+            .iter_mut()
+            .for_each(|count| *count += 1);
+    }
+}
+
+/// Split the tiles into the 12 single tiles and pair of kokushi musou (thirteen orphans), if they form it.
+fn kokushi(counts: &[u8; TILE_KINDS]) -> Option<Vec<Vec<usize>>> {
+    let orphans = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+    let total: u8 = counts.iter().sum();
+    if total != 14 || orphans.iter().any(|&tile| counts[tile] == 0) {
+        return None;
+    }
+    let pair = orphans.iter().find(|&&tile| counts[tile] == 2)?;
+    Some(
+        orphans
+            .iter()
+            .map(|&tile| vec![tile; counts[tile].into()])
+            .filter(|group| group[0] != *pair)
+            .chain([vec![*pair; 2]])
+            .collect(),
+    )
+}
+
+/// Write the tile index in the MPSZ notation (ex: `5m`, `1z`).
+fn mpsz_string(tile: usize) -> String {
+    match tile {
+        0..=26 => format!("{}{}", tile % 9 + 1, ['m', 'p', 's'][tile / 9]),
+        _ => format!("{}z", tile - 26),
+    }
+}
+
+/// Write the tile index in the hand notation (ex: `5m`, `Ew`, `rd`).
+fn tile_string(tile: usize) -> String {
+    group_string(&[tile], "")
+}
+
+/// Write the tiles of a group in the hand notation (ex: `123m`, `EEEw`, `rrrdo`), followed by the `meld` details.
+fn group_string(tiles: &[usize], meld: &str) -> String {
+    let mut out: String = tiles
+        .iter()
+        .map(|&tile| match tile {
+            0..=26 => char::from_digit((tile % 9 + 1) as u32, 10).unwrap(),
+            27..=30 => ['E', 'S', 'W', 'N'][tile - 27],
+            _ => ['w', 'g', 'r'][tile - 31],
+        })
+        .collect();
+    out.push(match tiles[0] {
+        0..=8 => 'm',
+        9..=17 => 'p',
+        18..=26 => 's',
+        27..=30 => 'w',
+        _ => 'd',
+    });
+    out.push_str(meld);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        from_unicode, is_mpsz, is_unicode, to_hand_tile, to_unicode_tile, waits, MpszHand,
+    };
+    use crate::hand::error::HandErr;
+
+    #[test]
+    fn parse_closed_hand() {
+        let hand = MpszHand::parse("123m456p789s11222z").unwrap();
+        assert_eq!(hand.win(), "Sw");
+        assert_eq!(
+            hand.arrangements().unwrap(),
+            vec![vec!["123m", "456p", "789s", "EEw", "SSSw"]]
+        );
+    }
+
+    #[test]
+    fn parse_called_hand() {
+        let hand = MpszHand::parse("234m0p67p55s c789s p777z").unwrap();
+        assert_eq!(hand.red_fives(), 1);
+        assert_eq!(
+            hand.arrangements().unwrap(),
+            vec![vec!["789soL", "rrrdo", "234m", "567p", "55s"]]
+        );
+
+        let hand = MpszHand::parse("234m55s567p c789s a1111z").unwrap();
+        assert_eq!(
+            hand.arrangements().unwrap(),
+            vec![vec!["789soL", "EEEEw", "234m", "55s", "567p"]]
+        );
+        assert_eq!(
+            hand.with_win("5s").unwrap().arrangements().unwrap(),
+            vec![vec!["789soL", "EEEEw", "234m", "567p", "55s"]]
+        );
+    }
+
+    #[test]
+    fn parse_seven_pairs_and_kokushi() {
+        let hand = MpszHand::parse("1133m5577p2299s11z").unwrap();
+        assert_eq!(
+            hand.arrangements().unwrap(),
+            vec![vec!["11m", "33m", "55p", "77p", "22s", "99s", "EEw"]]
+        );
+
+        let hand = MpszHand::parse("19m19p19s12345677z").unwrap();
+        assert_eq!(
+            hand.arrangements().unwrap(),
+            vec![vec![
+                "1m", "9m", "1p", "9p", "1s", "9s", "Ew", "Sw", "Ww", "Nw", "wd", "gd", "rrd"
+            ]]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_hands() {
+        assert!(matches!(
+            MpszHand::parse("123m456p789s1182z"),
+            Err(HandErr::InvalidGroup(_))
+        ));
+        assert!(matches!(
+            MpszHand::parse("123m456p789s1122"),
+            Err(HandErr::InvalidGroup(_))
+        ));
+        assert!(matches!(
+            MpszHand::parse("123m456p789s11z c246m"),
+            Err(HandErr::InvalidGroup(_))
+        ));
+        assert!(matches!(
+            MpszHand::parse("123m456p789s11z"),
+            Err(HandErr::InvalidShape(_))
+        ));
+        assert!(matches!(
+            MpszHand::parse("123m456p789s13577z")
+                .unwrap()
+                .arrangements(),
+            Err(HandErr::InvalidShape(_))
+        ));
+        assert_eq!(MpszHand::parse(""), Err(HandErr::NoHandTiles));
+    }
+
+    #[test]
+    fn find_waits() {
+        assert_eq!(waits("123m456p789s1122z").unwrap(), ["Ew", "Sw"]);
+        assert_eq!(waits("1133m5577p2299s1z").unwrap(), ["Ew"]);
+        assert_eq!(waits("19m19p19s1234567z").unwrap().len(), 13);
+        assert_eq!(waits("111m23p55s c789s p777z").unwrap(), ["1p", "4p"]);
+        assert_eq!(waits("1111m234p567p c789s").unwrap(), Vec::<String>::new());
+        assert!(matches!(
+            waits("123m456p789s11z"),
+            Err(HandErr::InvalidShape(_))
+        ));
+        assert!(matches!(
+            waits(&format!("{}m", "1".repeat(256))),
+            Err(HandErr::InvalidShape(_))
+        ));
+        assert!(matches!(
+            waits("11111m23456p789s"),
+            Err(HandErr::InvalidShape(_))
+        ));
+    }
+
+    #[test]
+    fn detect_notation() {
+        assert!(is_mpsz(&["123m456p789s11z".to_string()]));
+        assert!(is_mpsz(&["406m".to_string()]));
+        assert!(is_mpsz(&["123m".to_string(), "p555s".to_string()]));
+        assert!(!is_mpsz(&[
+            "123m".to_string(),
+            "1z3m".to_string(),
+            "EEEw".to_string()
+        ]));
+        assert!(!is_mpsz(&[
+            "123m".to_string(),
+            "555po".to_string(),
+            "EEEw".to_string()
+        ]));
+        assert_eq!(to_hand_tile("7z").unwrap(), "rd");
+        assert!(to_hand_tile("12m").is_err());
+    }
+
+    #[test]
+    fn unicode_tiles() {
+        let tiles = [
+            "1m", "9m", "1p", "9p", "1s", "9s", "Ew", "Sw", "Ww", "Nw", "wd", "gd", "rd",
+        ];
+        let unicode: String = tiles.iter().map(|t| to_unicode_tile(t).unwrap()).collect();
+        assert_eq!(unicode, "🀇🀏🀙🀡🀐🀘🀀🀁🀂🀃🀆🀅🀄");
+        assert_eq!(from_unicode(&unicode), "1m9m1p9p1s9s1z2z3z4z5z6z7z");
+        for tile in tiles {
+            let unicode = to_unicode_tile(tile).unwrap().to_string();
+            assert_eq!(to_hand_tile(&unicode).unwrap(), tile);
+        }
+        assert!(is_unicode(&["123m".to_string(), "🀄🀄".to_string()]));
+        assert!(!is_unicode(&["123m".to_string()]));
+    }
+}
@@ -0,0 +1,288 @@
+//! Replay of game logs, to check the score of every win against mahc.
+
+use crate::calc::get_hand_score;
+use crate::hand::error::HandErr;
+use crate::notation::MpszHand;
+use crate::yaku::Yaku;
+
+pub mod majsoul;
+pub mod tenhou;
+
+/// A win recorded in a game log, with the winning hand rebuilt from the draws, discards and calls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedWin {
+    /// Name of the round (ex: `East 1`).
+    pub round: String,
+    pub honba: u16,
+    /// Seat index (0-3) of the winning player.
+    pub winner: usize,
+    /// Seat index (0-3) of the player who dealt in, or `None` for a tsumo.
+    pub loser: Option<usize>,
+    /// Hand tiles in the MPSZ notation, concealed tiles first and then each call (ex: `["234m0p67p11z", "c789s"]`).
+    pub tiles: Vec<String>,
+    /// Winning tile in the MPSZ notation.
+    pub win: String,
+    /// Seat wind in the MPSZ notation.
+    pub seat: String,
+    /// Round (prevalent) wind in the MPSZ notation.
+    pub prev: String,
+    /// Dora counted from the dora and ura dora indicators, not counting red fives.
+    pub dora: u16,
+    pub riichi: bool,
+    pub doubleriichi: bool,
+    pub ippatsu: bool,
+    /// Haitei or houtei (win on the last tile).
+    pub haitei: bool,
+    pub rinshan: bool,
+    pub chankan: bool,
+    /// Tenhou or chiihou.
+    pub tenhou: bool,
+    /// Score written in the log.
+    pub logged: LoggedScore,
+}
+
+impl LoggedWin {
+    pub fn is_tsumo(&self) -> bool {
+        self.loser.is_none()
+    }
+
+    pub fn is_dealer(&self) -> bool {
+        self.seat == "1z"
+    }
+
+    /// Score the hand with mahc, keeping the highest scoring way to split it into groups.
+    pub fn score(&self) -> Result<ScoredWin, HandErr> {
+        let hand = MpszHand::parse(&self.tiles.join(" "))?.with_win(&self.win)?;
+        let seat = crate::notation::to_hand_tile(&self.seat)?;
+        let prev = crate::notation::to_hand_tile(&self.prev)?;
+
+        let mut best: Option<ScoredWin> = None;
+        let mut error = None;
+        for tiles in hand.arrangements()? {
+            let result = get_hand_score(
+                tiles,
+                hand.win(),
+                self.dora.saturating_add(hand.red_fives()),
+                seat.clone(),
+                prev.clone(),
+                self.is_tsumo(),
+                self.riichi,
+                self.doubleriichi,
+                self.ippatsu,
+                self.haitei,
+                self.rinshan,
+                self.chankan,
+                self.tenhou,
+                0,
+            );
+            match result {
+                Ok((payment, yaku, _, han_and_fu, _)) => {
+                    let scored = ScoredWin {
+                        han: han_and_fu[0],
+                        fu: han_and_fu[1],
+                        points: self.points(&payment),
+                        yaku,
+                    };
+                    if best.as_ref().is_none_or(|b| {
+                        (scored.points, scored.han, scored.fu) > (b.points, b.han, b.fu)
+                    }) {
+                        best = Some(scored);
+                    }
+                }
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        match (best, error) {
+            (Some(best), _) => Ok(best),
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(HandErr::NoHandTiles),
+        }
+    }
+
+    /// Total points won from the payment amounts, without honba or riichi deposits.
+    ///
+    /// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the `payment`.
+    pub fn points(&self, payment: &[u32]) -> u32 {
+        match (self.is_dealer(), self.is_tsumo()) {
+            (true, false) => payment[0],
+            (true, true) => payment[1] * 3,
+            (false, false) => payment[2],
+            (false, true) => payment[3] * 2 + payment[4],
+        }
+    }
+
+    /// Score the hand and compare it with the logged score. An empty list means mahc agrees with the log.
+    pub fn verify(&self) -> Vec<Discrepancy> {
+        let scored = match self.score() {
+            Ok(scored) => scored,
+            Err(e) => return vec![Discrepancy::Error(e)],
+        };
+
+        let mut found = Vec::new();
+        if let Some(han) = self.logged.han.filter(|&han| han != scored.han) {
+            found.push(Discrepancy::Han {
+                logged: han,
+                scored: scored.han,
+            });
+        }
+        if let Some(fu) = self.logged.fu.filter(|&fu| fu != scored.fu) {
+            found.push(Discrepancy::Fu {
+                logged: fu,
+                scored: scored.fu,
+            });
+        }
+        if self.logged.points != scored.points {
+            found.push(Discrepancy::Points {
+                logged: self.logged.points,
+                scored: scored.points,
+            });
+        }
+        found
+    }
+}
+
+/// Score of a win as written in a game log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedScore {
+    /// Total han, including dora. `None` for yakuman.
+    pub han: Option<u16>,
+    /// Fu, if the log gives it (limit hands usually do not).
+    pub fu: Option<u16>,
+    /// Total points won, without honba or riichi deposits.
+    pub points: u32,
+    /// Yaku as written in the log (names in Tenhou logs, ids in Mahjong Soul records).
+    pub yaku: Vec<String>,
+}
+
+/// Score of a logged win calculated by mahc.
+#[derive(Debug, PartialEq)]
+pub struct ScoredWin {
+    pub han: u16,
+    pub fu: u16,
+    /// Total points won, without honba or riichi deposits.
+    pub points: u32,
+    pub yaku: Vec<Yaku>,
+}
+
+/// A difference between the logged score of a win and the score calculated by mahc.
+#[derive(Debug, PartialEq)]
+pub enum Discrepancy {
+    Han {
+        logged: u16,
+        scored: u16,
+    },
+    Fu {
+        logged: u16,
+        scored: u16,
+    },
+    Points {
+        logged: u32,
+        scored: u32,
+    },
+    /// The hand could not be scored at all.
+    Error(HandErr),
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Han { logged, scored } => write!(f, "han: logged {}, scored {}", logged, scored),
+            Self::Fu { logged, scored } => write!(f, "fu: logged {}, scored {}", logged, scored),
+            Self::Points { logged, scored } => {
+                write!(f, "points: logged {}, scored {}", logged, scored)
+            }
+            Self::Error(e) => write!(f, "error: {}", e),
+        }
+    }
+}
+
+/// Tiles of a rebuilt hand, with each call as its MPSZ marker and tiles.
+///
+/// Tiles are numbered as in Tenhou logs: `11`-`19`, `21`-`29` and `31`-`39` for the number suits, `41`-`47` for honors and `51`-`53` for the red fives.
+#[derive(Debug, Default)]
+struct Hand {
+    closed: Vec<u8>,
+    calls: Vec<(char, Vec<u8>)>,
+}
+
+impl Hand {
+    fn remove(&mut self, tile: u8) -> Result<(), HandErr> {
+        let index = self
+            .closed
+            .iter()
+            .position(|&t| t == tile)
+            .ok_or_else(|| HandErr::InvalidLog(format!("tile {} is not in the hand", tile)))?;
+        self.closed.remove(index);
+        Ok(())
+    }
+
+    /// Every tile of the hand, including the called ones.
+    fn tiles(&self) -> impl Iterator<Item = u8> + '_ {
+        self.closed
+            .iter()
+            .chain(self.calls.iter().flat_map(|(_, tiles)| tiles))
+            .copied()
+    }
+
+    /// Write the hand in the MPSZ notation, concealed tiles first.
+    fn tokens(&self) -> Result<Vec<String>, HandErr> {
+        let mut tokens = vec![mpsz(&self.closed)?];
+        for (marker, tiles) in &self.calls {
+            tokens.push(format!("{}{}", marker, mpsz(tiles)?));
+        }
+        Ok(tokens)
+    }
+}
+
+/// Get the tile without its red five marking.
+fn plain(tile: u8) -> u8 {
+    match tile {
+        51..=53 => (tile - 50) * 10 + 5,
+        _ => tile,
+    }
+}
+
+/// Count the dora of the hand for the given indicators.
+fn count_dora(hand: &Hand, indicators: &[u16]) -> u16 {
+    indicators
+        .iter()
+        .map(|&indicator| {
+            let indicator = plain(indicator as u8);
+            let dora = match (indicator / 10, indicator % 10) {
+                (1..=3, 9) => indicator - 8,
+                (4, 4) => 41,
+                (4, 7) => 45,
+                _ => indicator + 1,
+            };
+            hand.tiles().filter(|&tile| plain(tile) == dora).count() as u16
+        })
+        .sum()
+}
+
+/// Write tiles in the MPSZ notation, sorted and grouped by suit (ex: `234m0p67p`).
+fn mpsz(tiles: &[u8]) -> Result<String, HandErr> {
+    let mut sorted = tiles.to_vec();
+    sorted.sort_by_key(|&tile| (plain(tile), tile));
+
+    let mut out = String::new();
+    let mut suit = None;
+    for tile in sorted {
+        let (value, tile_suit) = match tile {
+            11..=19 | 21..=29 | 31..=39 | 41..=47 => (tile % 10, tile / 10),
+            51..=53 => (0, tile - 50),
+            _ => return Err(HandErr::InvalidLog(format!("invalid tile {}", tile))),
+        };
+        let tile_suit = ['m', 'p', 's', 'z'][tile_suit as usize - 1];
+        if let Some(suit) = suit.filter(|&suit| suit != tile_suit) {
+            out.push(suit);
+        }
+        out.push(char::from_digit(value as u32, 10).unwrap());
+        suit = Some(tile_suit);
+    }
+    if let Some(suit) = suit {
+        out.push(suit);
+    }
+    Ok(out)
+}
@@ -0,0 +1,321 @@
+//! Mahjong Soul game records (paipu), exported as JSON.
+//!
+//! The record is a list of `actions`, each with the `name` of the record type and its `data`.
+//! Rounds start with a `RecordNewRound` (round wind `chang`, dealer `ju` and honba `ben`),
+//! and wins are the `hules` of a `RecordHule`, holding the concealed `hand`, the called melds (`ming`),
+//! the winning tile (`hu_tile`), the dora indicators and the logged score.
+//! Tiles are written in the MPSZ notation (ex: `0p` for the red five of pinzu, `7z` for the red dragon).
+
+use serde_json::Value;
+
+use super::{count_dora, Hand, LoggedScore, LoggedWin};
+use crate::hand::error::HandErr;
+
+const ROUND_WINDS: [&str; 4] = ["East", "South", "West", "North"];
+
+/// Ids of the yaku (fan) that depend on the situation of the win, rather than on the hand.
+const RIICHI: u64 = 2;
+const CHANKAN: u64 = 3;
+const RINSHAN: u64 = 4;
+const HAITEI: u64 = 5;
+const HOUTEI: u64 = 6;
+const DOUBLE_RIICHI: u64 = 18;
+const IPPATSU: u64 = 30;
+const TENHOU: u64 = 35;
+const CHIIHOU: u64 = 36;
+
+/// Read every win of a Mahjong Soul record.
+///
+/// The actions can be at the top level of the record, or under its `data`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::replay::majsoul;
+///
+/// assert!(majsoul::parse(r#"{"head": {}, "data": {"actions": []}}"#).unwrap().is_empty());
+/// assert!(majsoul::parse(r#"{"head": {}}"#).is_err());
+/// ```
+pub fn parse(contents: &str) -> Result<Vec<LoggedWin>, HandErr> {
+    let record: Value =
+        serde_json::from_str(contents).map_err(|e| HandErr::InvalidLog(e.to_string()))?;
+    let actions = record
+        .get("actions")
+        .or_else(|| record.pointer("/data/actions"))
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing the `actions` array"))?;
+
+    let mut round: Option<(usize, usize, u16)> = None;
+    let mut last_discard = None;
+    let mut wins = Vec::new();
+    for action in actions {
+        let data = &action["data"];
+        match action["name"].as_str() {
+            Some("RecordNewRound") => {
+                let number = |key: &str| {
+                    data[key]
+                        .as_u64()
+                        .ok_or_else(|| HandErr::InvalidLog(format!("missing the round `{}`", key)))
+                };
+                round = Some((
+                    number("chang")? as usize % 4,
+                    number("ju")? as usize % 4,
+                    number("ben")? as u16,
+                ));
+                last_discard = None;
+            }
+            // The tile robbed by a chankan is added to a kan rather than discarded.
+            Some("RecordDiscardTile" | "RecordAnGangAddGang") => {
+                last_discard = data["seat"].as_u64().map(|seat| seat as usize);
+            }
+            Some("RecordHule") => {
+                let (chang, ju, honba) =
+                    round.ok_or_else(|| invalid("a win must come after the start of a round"))?;
+                let hules = data["hules"]
+                    .as_array()
+                    .ok_or_else(|| invalid("missing the `hules` of a win"))?;
+                for hule in hules {
+                    wins.push(parse_hule(hule, chang, ju, honba, last_discard)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(wins)
+}
+
+fn invalid(reason: &str) -> HandErr {
+    HandErr::InvalidLog(reason.to_string())
+}
+
+/// Read a single win, in the round with the wind `chang` and dealer `ju`.
+fn parse_hule(
+    hule: &Value,
+    chang: usize,
+    ju: usize,
+    honba: u16,
+    last_discard: Option<usize>,
+) -> Result<LoggedWin, HandErr> {
+    let winner = hule["seat"]
+        .as_u64()
+        .map(|seat| seat as usize)
+        .filter(|&seat| seat < 4)
+        .ok_or_else(|| invalid("seats must be numbers from 0 to 3"))?;
+    let tsumo = hule["zimo"].as_bool().unwrap_or_default();
+    let loser = match tsumo {
+        true => None,
+        false => Some(
+            last_discard
+                .filter(|&seat| seat != winner)
+                .ok_or_else(|| invalid("a ron must come after a discard of another player"))?,
+        ),
+    };
+
+    let win = tile_code(
+        hule["hu_tile"]
+            .as_str()
+            .ok_or_else(|| invalid("missing the `hu_tile` of a win"))?,
+    )?;
+    let mut hand = Hand {
+        closed: tiles(&hule["hand"])?,
+        calls: Vec::new(),
+    };
+    hand.closed.push(win);
+    for ming in strings(&hule["ming"])? {
+        hand.calls.push(meld(ming)?);
+    }
+
+    let fans: Vec<(u64, u16)> = hule["fans"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|fan| {
+            let id = fan["id"].as_u64();
+            let val = fan["val"].as_u64().and_then(|val| u16::try_from(val).ok());
+            id.zip(val)
+                .ok_or_else(|| invalid("a yaku needs its `id` and `val`"))
+        })
+        .collect::<Result<_, _>>()?;
+    let has = |ids: &[u64]| fans.iter().any(|(id, _)| ids.contains(id));
+
+    let riichi = has(&[RIICHI]);
+    let doubleriichi = has(&[DOUBLE_RIICHI]);
+    let indicators = |key: &str| -> Result<Vec<u16>, HandErr> {
+        Ok(tiles(&hule[key])?.into_iter().map(u16::from).collect())
+    };
+    let mut dora = count_dora(&hand, &indicators("doras")?);
+    if riichi || doubleriichi {
+        dora += count_dora(&hand, &indicators("li_doras")?);
+    }
+
+    let dealer = winner == ju;
+    let points = |key: &str| hule[key].as_u64().unwrap_or_default() as u32;
+    let points = match (tsumo, dealer) {
+        (false, _) => points("point_rong"),
+        (true, true) => points("point_zimo_xian") * 3,
+        (true, false) => points("point_zimo_xian") * 2 + points("point_zimo_qin"),
+    };
+    let yakuman = hule["yiman"].as_bool().unwrap_or_default();
+
+    Ok(LoggedWin {
+        round: format!("{} {}", ROUND_WINDS[chang], ju + 1),
+        honba,
+        winner,
+        loser,
+        tiles: hand.tokens()?,
+        win: super::mpsz(&[win])?,
+        seat: format!("{}z", (winner + 4 - ju) % 4 + 1),
+        prev: format!("{}z", chang + 1),
+        dora,
+        riichi,
+        doubleriichi,
+        ippatsu: has(&[IPPATSU]),
+        haitei: has(&[HAITEI, HOUTEI]),
+        rinshan: has(&[RINSHAN]),
+        chankan: has(&[CHANKAN]),
+        tenhou: has(&[TENHOU, CHIIHOU]),
+        logged: LoggedScore {
+            han: match yakuman {
+                true => None,
+                false => hule["count"].as_u64().map(|han| han as u16),
+            },
+            fu: hule["fu"].as_u64().filter(|_| !yakuman).map(|fu| fu as u16),
+            points,
+            yaku: fans.iter().map(|(id, _)| id.to_string()).collect(),
+        },
+    })
+}
+
+/// Read an array of strings, which may be missing.
+fn strings(value: &Value) -> Result<Vec<&str>, HandErr> {
+    value
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|s| {
+            s.as_str()
+                .ok_or_else(|| invalid("expected an array of tiles"))
+        })
+        .collect()
+}
+
+fn tiles(value: &Value) -> Result<Vec<u8>, HandErr> {
+    strings(value)?.into_iter().map(tile_code).collect()
+}
+
+/// Convert a tile in the MPSZ notation (ex: `5m`, `0p`, `7z`) to the tile numbering of Tenhou logs.
+fn tile_code(tile: &str) -> Result<u8, HandErr> {
+    let bad_tile = || HandErr::InvalidLog(format!("invalid tile `{}`", tile));
+    let mut chars = tile.chars();
+    let (Some(value), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+        return Err(bad_tile());
+    };
+    let value = value.to_digit(10).ok_or_else(bad_tile)? as u8;
+    let suit = match suit {
+        'm' => 1,
+        'p' => 2,
+        's' => 3,
+        'z' => 4,
+        _ => return Err(bad_tile()),
+    };
+    match (value, suit) {
+        (0, 4) | (8..=9, 4) => Err(bad_tile()),
+        (0, _) => Ok(50 + suit),
+        _ => Ok(suit * 10 + value),
+    }
+}
+
+/// Read a called meld (ex: `shunzi(4s,5s,6s)`, `kezi(7z,7z,7z)`, `minggang(...)` or `angang(...)`) as its MPSZ marker and tiles.
+fn meld(ming: &str) -> Result<(char, Vec<u8>), HandErr> {
+    let bad_meld = || HandErr::InvalidLog(format!("invalid meld `{}`", ming));
+    let (kind, tiles) = ming
+        .strip_suffix(')')
+        .and_then(|ming| ming.split_once('('))
+        .ok_or_else(bad_meld)?;
+    let marker = match kind {
+        "shunzi" => 'c',
+        "kezi" => 'p',
+        "minggang" => 'k',
+        "angang" => 'a',
+        _ => return Err(bad_meld()),
+    };
+    let tiles = tiles
+        .split(',')
+        .map(tile_code)
+        .collect::<Result<Vec<u8>, HandErr>>()?;
+    Ok((marker, tiles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ron on the first discard of the dealer, and a tsumo after a pon of red dragons.
+    const RECORD: &str = r#"{
+        "head": {"uuid": "test"},
+        "data": {"actions": [
+            {"name": "RecordNewRound", "data": {"chang": 0, "ju": 0, "ben": 0, "doras": ["7z"]}},
+            {"name": "RecordDiscardTile", "data": {"seat": 0, "tile": "5p"}},
+            {"name": "RecordHule", "data": {"hules": [{
+                "seat": 1, "zimo": false, "qinjia": false, "liqi": false,
+                "hand": ["1m", "2m", "3m", "4m", "5m", "6m", "7m", "8m", "9m", "2p", "3p", "4p", "5p"],
+                "ming": [], "hu_tile": "5p", "doras": ["7z"],
+                "count": 2, "fu": 40, "yiman": false, "fans": [{"val": 2, "id": 16}],
+                "point_rong": 2600
+            }]}},
+            {"name": "RecordNewRound", "data": {"chang": 0, "ju": 1, "ben": 0, "doras": ["6z"]}},
+            {"name": "RecordDiscardTile", "data": {"seat": 1, "tile": "7z"}},
+            {"name": "RecordChiPengGang", "data": {"seat": 2, "tiles": ["7z", "7z", "7z"]}},
+            {"name": "RecordDiscardTile", "data": {"seat": 2, "tile": "2s"}},
+            {"name": "RecordHule", "data": {"hules": [{
+                "seat": 2, "zimo": true, "qinjia": false, "liqi": false,
+                "hand": ["2m", "3m", "4m", "0p", "6p", "7p", "1s", "7s", "8s", "9s"],
+                "ming": ["kezi(7z,7z,7z)"], "hu_tile": "1s", "doras": ["6z"],
+                "count": 5, "fu": 30, "yiman": false,
+                "fans": [{"val": 1, "id": 9}, {"val": 3, "id": 31}, {"val": 1, "id": 32}],
+                "point_zimo_qin": 4000, "point_zimo_xian": 2000
+            }]}}
+        ]}
+    }"#;
+
+    #[test]
+    fn rebuild_wins() {
+        let wins = parse(RECORD).unwrap();
+        assert_eq!(wins.len(), 2);
+
+        let ron = &wins[0];
+        assert_eq!(
+            (ron.round.as_str(), ron.winner, ron.loser),
+            ("East 1", 1, Some(0))
+        );
+        assert_eq!(ron.tiles, vec!["123456789m23455p"]);
+        assert_eq!((ron.seat.as_str(), ron.dora), ("2z", 0));
+        assert_eq!(ron.logged.points, 2600);
+
+        let tsumo = &wins[1];
+        assert_eq!(
+            (tsumo.round.as_str(), tsumo.winner, tsumo.loser),
+            ("East 2", 2, None)
+        );
+        assert_eq!(tsumo.tiles, vec!["234m067p11789s", "p777z"]);
+        assert_eq!((tsumo.win.as_str(), tsumo.dora), ("1s", 3));
+        assert_eq!(tsumo.logged.points, 8000);
+
+        assert_eq!(ron.verify(), vec![]);
+        assert_eq!(tsumo.verify(), vec![]);
+    }
+
+    #[test]
+    fn read_tiles_and_melds() {
+        assert_eq!(tile_code("0p").unwrap(), 52);
+        assert_eq!(tile_code("7z").unwrap(), 47);
+        assert!(tile_code("8z").is_err());
+        assert!(tile_code("5x").is_err());
+
+        assert_eq!(meld("shunzi(4s,0s,6s)").unwrap(), ('c', vec![34, 53, 36]));
+        assert_eq!(meld("angang(1z,1z,1z,1z)").unwrap(), ('a', vec![41; 4]));
+        assert!(meld("kezi 7z").is_err());
+    }
+}
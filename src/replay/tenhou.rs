@@ -0,0 +1,472 @@
+//! Tenhou JSON logs, as given by the Tenhou log viewer (`tenhou.net/6`).
+//!
+//! Each round of the `log` array holds the round info, the scores, the dora and ura dora indicators,
+//! the starting hand (haipai), draws and discards of every player, and the result of the round.
+//! Tiles are numbered `11`-`19` for manzu, `21`-`29` for pinzu, `31`-`39` for souzu, `41`-`47` for honors
+//! (East, South, West, North, white, green and red dragon), and `51`-`53` for the red fives,
+//! which is also how [`Hand`](super::Hand) numbers its tiles.
+
+use serde_json::Value;
+
+use super::{count_dora, mpsz, plain, Hand, LoggedScore, LoggedWin};
+use crate::hand::error::HandErr;
+
+/// Result of a round won by one or more players.
+const AGARI: &str = "和了";
+
+/// Discard standing for the tile just drawn (tsumogiri).
+const TSUMOGIRI: u8 = 60;
+
+const ROUND_WINDS: [&str; 4] = ["East", "South", "West", "North"];
+
+/// Read every win of a Tenhou JSON log.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::replay::tenhou;
+///
+/// assert!(tenhou::parse(r#"{"log": []}"#).unwrap().is_empty());
+/// assert!(tenhou::parse(r#"{"rounds": []}"#).is_err());
+/// ```
+pub fn parse(contents: &str) -> Result<Vec<LoggedWin>, HandErr> {
+    let log: Value =
+        serde_json::from_str(contents).map_err(|e| HandErr::InvalidLog(e.to_string()))?;
+    let rounds = log
+        .get("log")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing the `log` array"))?;
+
+    let mut wins = Vec::new();
+    for round in rounds {
+        wins.extend(parse_round(round)?);
+    }
+    Ok(wins)
+}
+
+fn invalid(reason: &str) -> HandErr {
+    HandErr::InvalidLog(reason.to_string())
+}
+
+/// Read the wins of a single round, if it did not end in a draw.
+fn parse_round(round: &Value) -> Result<Vec<LoggedWin>, HandErr> {
+    let round = round
+        .as_array()
+        .filter(|round| round.len() >= 17)
+        .ok_or_else(|| {
+            invalid("a round needs its info, scores, dora, hands, draws, discards and result")
+        })?;
+    let info = numbers(&round[0])?;
+    let (Some(&kyoku), Some(&honba)) = (info.first(), info.get(1)) else {
+        return Err(invalid("the round info needs the round and honba numbers"));
+    };
+    let indicators = numbers(&round[2])?;
+    let ura_indicators = numbers(&round[3])?;
+
+    let result = round[16]
+        .as_array()
+        .ok_or_else(|| invalid("the round result must be an array"))?;
+    if result.first().and_then(Value::as_str) != Some(AGARI) {
+        return Ok(Vec::new());
+    }
+
+    let dealer = kyoku as usize % 4;
+    let player = |seat: usize| -> Result<Player, HandErr> {
+        let entries = |index: usize| {
+            round[4 + seat * 3 + index]
+                .as_array()
+                .map(Vec::as_slice)
+                .ok_or_else(|| invalid("hands, draws and discards must be arrays"))
+        };
+        let haipai = numbers(&round[4 + seat * 3])?
+            .into_iter()
+            .map(|tile| {
+                u8::try_from(tile).map_err(|_| invalid("tiles must be numbers from 11 to 53"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((haipai, entries(1)?, entries(2)?))
+    };
+
+    let mut wins = Vec::new();
+    for details in result[1..].chunks(2).filter_map(|pair| pair.get(1)) {
+        let details = details
+            .as_array()
+            .filter(|details| details.len() >= 4)
+            .ok_or_else(|| invalid("a win needs the winner, loser, liable player and score"))?;
+        let seat = |value: &Value| {
+            value
+                .as_u64()
+                .map(|seat| seat as usize)
+                .filter(|&seat| seat < 4)
+                .ok_or_else(|| invalid("seats must be numbers from 0 to 3"))
+        };
+        let winner = seat(&details[0])?;
+        let from = seat(&details[1])?;
+        let yaku: Vec<&str> = details[4..].iter().filter_map(Value::as_str).collect();
+        let text = details[3]
+            .as_str()
+            .ok_or_else(|| invalid("the score must be a string"))?;
+
+        let (haipai, draws, discards) = player(winner)?;
+        let mut hand = Hand::rebuild(haipai, draws, discards)?;
+        let loser = (from != winner).then_some(from);
+        let win = match loser {
+            None => match draws.last().map(Entry::from_value).transpose()? {
+                Some(Entry::Tile(tile)) => tile,
+                _ => return Err(invalid("a tsumo must end with a drawn tile")),
+            },
+            Some(loser) => {
+                let (_, draws, discards) = player(loser)?;
+                let tile = last_discard(draws, discards)?;
+                hand.closed.push(tile);
+                tile
+            }
+        };
+
+        let has = |name: &str| yaku.iter().any(|y| yaku_name(y) == name);
+        let riichi = has("立直");
+        let doubleriichi = has("両立直");
+        let mut dora = count_dora(&hand, &indicators);
+        if riichi || doubleriichi {
+            dora += count_dora(&hand, &ura_indicators);
+        }
+
+        wins.push(LoggedWin {
+            round: format!(
+                "{} {}",
+                ROUND_WINDS[kyoku as usize / 4 % 4],
+                kyoku as usize % 4 + 1
+            ),
+            honba,
+            winner,
+            loser,
+            tiles: hand.tokens()?,
+            win: mpsz(&[win])?,
+            seat: format!("{}z", (winner + 4 - dealer) % 4 + 1),
+            prev: format!("{}z", kyoku as usize / 4 % 4 + 1),
+            dora,
+            riichi,
+            doubleriichi,
+            ippatsu: has("一発"),
+            haitei: has("海底摸月") || has("河底撈魚"),
+            rinshan: has("嶺上開花"),
+            chankan: has("槍槓"),
+            tenhou: has("天和") || has("地和"),
+            logged: logged_score(text, &yaku)?,
+        });
+    }
+    Ok(wins)
+}
+
+/// Starting hand, draws and discards of a player.
+type Player<'a> = (Vec<u8>, &'a [Value], &'a [Value]);
+
+/// Read an array of numbers (ex: the round info, or the dora indicators).
+fn numbers(value: &Value) -> Result<Vec<u16>, HandErr> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid("expected an array of numbers"))?
+        .iter()
+        .map(|n| {
+            n.as_u64()
+                .and_then(|n| u16::try_from(n).ok())
+                .ok_or_else(|| invalid("expected an array of numbers"))
+        })
+        .collect()
+}
+
+/// A draw or discard of a player.
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Tile(u8),
+    /// A call, with its marker, the number of tiles written before the marker, and every tile of the meld.
+    Call(char, usize, Vec<u8>),
+}
+
+impl Entry {
+    fn from_value(value: &Value) -> Result<Self, HandErr> {
+        if let Some(tile) = value.as_u64() {
+            return u8::try_from(tile)
+                .map(Self::Tile)
+                .map_err(|_| invalid("tiles must be numbers from 11 to 53"));
+        }
+        let call = value
+            .as_str()
+            .ok_or_else(|| invalid("draws and discards must be numbers or strings"))?;
+        // A riichi discard is the discarded tile behind an `r`.
+        if let Some(tile) = call.strip_prefix('r') {
+            return tile
+                .parse()
+                .map(Self::Tile)
+                .map_err(|_| invalid("a riichi must be followed by the discarded tile"));
+        }
+
+        let mut marker = None;
+        let mut tiles = Vec::new();
+        let mut digits = String::new();
+        for c in call.chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                if digits.len() == 2 {
+                    tiles.push(digits.parse().unwrap());
+                    digits.clear();
+                }
+            } else if marker.is_none() && digits.is_empty() {
+                marker = Some((c, tiles.len()));
+            } else {
+                return Err(HandErr::InvalidLog(format!("invalid call `{}`", call)));
+            }
+        }
+        match marker {
+            Some((marker @ ('c' | 'p' | 'm' | 'k' | 'a'), position))
+                if digits.is_empty() && position < tiles.len() =>
+            {
+                Ok(Self::Call(marker, position, tiles))
+            }
+            _ => Err(HandErr::InvalidLog(format!("invalid call `{}`", call))),
+        }
+    }
+}
+
+/// Get the tile of the last discard of a player, which is the tile robbed by a chankan when the last discard is a kan.
+fn last_discard(draws: &[Value], discards: &[Value]) -> Result<u8, HandErr> {
+    let index = discards
+        .len()
+        .checked_sub(1)
+        .ok_or_else(|| invalid("the player who dealt in has no discards"))?;
+    match Entry::from_value(&discards[index])? {
+        Entry::Tile(TSUMOGIRI) => match draws.get(index).map(Entry::from_value).transpose()? {
+            Some(Entry::Tile(tile)) => Ok(tile),
+            _ => Err(invalid("a tsumogiri must follow a drawn tile")),
+        },
+        Entry::Tile(tile) => Ok(tile),
+        Entry::Call(_, position, tiles) => Ok(tiles[position]),
+    }
+}
+
+impl Hand {
+    /// Play the draws and discards of a player from their starting hand.
+    ///
+    /// Each discard follows the draw with the same index, and a tsumogiri discards that drawn tile.
+    fn rebuild(haipai: Vec<u8>, draws: &[Value], discards: &[Value]) -> Result<Self, HandErr> {
+        let mut hand = Self {
+            closed: haipai,
+            calls: Vec::new(),
+        };
+        for (index, draw) in draws.iter().enumerate() {
+            let draw = Entry::from_value(draw)?;
+            match &draw {
+                Entry::Tile(tile) => hand.closed.push(*tile),
+                Entry::Call(marker, position, tiles) => {
+                    let marker = match marker {
+                        'c' => 'c',
+                        'p' => 'p',
+                        'm' => 'k',
+                        _ => return Err(invalid("only chi, pon and open kan are drawn")),
+                    };
+                    for (i, &tile) in tiles.iter().enumerate() {
+                        if i != *position {
+                            hand.remove(tile)?;
+                        }
+                    }
+                    hand.calls.push((marker, tiles.clone()));
+                }
+            }
+
+            let Some(discard) = discards.get(index) else {
+                continue;
+            };
+            match Entry::from_value(discard)? {
+                Entry::Tile(0) => {}
+                Entry::Tile(TSUMOGIRI) => match draw {
+                    Entry::Tile(tile) => hand.remove(tile)?,
+                    _ => return Err(invalid("a tsumogiri must follow a drawn tile")),
+                },
+                Entry::Tile(tile) => hand.remove(tile)?,
+                Entry::Call('a', _, tiles) => {
+                    for &tile in &tiles {
+                        hand.remove(tile)?;
+                    }
+                    hand.calls.push(('a', tiles));
+                }
+                Entry::Call('k', position, tiles) => {
+                    let added = tiles[position];
+                    hand.remove(added)?;
+                    let pon = hand
+                        .calls
+                        .iter_mut()
+                        .find(|(marker, pon)| *marker == 'p' && plain(pon[0]) == plain(added))
+                        .ok_or_else(|| invalid("an added kan needs a pon of the same tile"))?;
+                    *pon = ('s', tiles);
+                }
+                Entry::Call(..) => return Err(invalid("only closed and added kan are discarded")),
+            }
+        }
+        Ok(hand)
+    }
+}
+
+/// Get the name of a logged yaku (ex: `立直` for `立直(1飜)`).
+fn yaku_name(yaku: &str) -> &str {
+    yaku.split('(').next().unwrap_or(yaku)
+}
+
+/// Read the logged score, written as `40符2飜2600点` for a ron, `30符1飜300-500点` for a non-dealer tsumo,
+/// `30符2飜1000点∀` for a dealer tsumo, and without fu and han for limit hands (ex: `満貫8000点`).
+fn logged_score(text: &str, yaku: &[&str]) -> Result<LoggedScore, HandErr> {
+    let bad_score = || HandErr::InvalidLog(format!("invalid score `{}`", text));
+
+    let fu = match text.split_once('符') {
+        Some((fu, _)) => Some(fu.parse().map_err(|_| bad_score())?),
+        None => None,
+    };
+
+    let all = text.ends_with('∀');
+    let body = text.trim_end_matches('∀').trim_end_matches('点');
+    let payment = body
+        .rsplit(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .next()
+        .unwrap_or_default();
+    let parse = |points: &str| points.parse::<u32>().map_err(|_| bad_score());
+    let points = match payment.split_once('-') {
+        Some((non_dealer, dealer)) => parse(non_dealer)? * 2 + parse(dealer)?,
+        None if all => parse(payment)? * 3,
+        None => parse(payment)?,
+    };
+
+    let mut han = Some(0u16);
+    for y in yaku {
+        let value = y
+            .rsplit_once('(')
+            .and_then(|(_, value)| value.strip_suffix(')'))
+            .ok_or_else(|| HandErr::InvalidLog(format!("invalid yaku `{}`", y)))?;
+        match value.strip_suffix('飜') {
+            Some(value) => {
+                let value: u16 = value
+                    .parse()
+                    .map_err(|_| HandErr::InvalidLog(format!("invalid yaku `{}`", y)))?;
+                han = han.map(|han| han + value);
+            }
+            // Yakuman are written as `(役満)`.
+            None => han = None,
+        }
+    }
+
+    Ok(LoggedScore {
+        han,
+        fu,
+        points,
+        yaku: yaku.iter().map(|y| yaku_name(y).to_string()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::Discrepancy;
+
+    /// A ron on the first discard of the dealer, a tsumo after a pon of red dragons, and a draw.
+    const LOG: &str = r#"{
+        "title": ["", ""],
+        "name": ["A", "B", "C", "D"],
+        "log": [
+            [
+                [0, 0, 0], [25000, 25000, 25000, 25000], [47], [],
+                [11, 11, 21, 22, 23, 31, 32, 33, 41, 41, 42, 43, 25], [44], [25],
+                [11, 12, 13, 14, 15, 16, 17, 18, 19, 22, 23, 24, 25], [], [],
+                [], [], [],
+                [], [], [],
+                ["和了", [-2600, 2600, 0, 0], [1, 0, 1, "40符2飜2600点", "一気通貫(2飜)"]]
+            ],
+            [
+                [1, 0, 0], [22400, 27600, 25000, 25000], [46], [],
+                [], [], [],
+                [47, 11, 11, 12, 21, 21, 22, 33, 34, 35, 41, 42, 43], [11], [47],
+                [47, 47, 12, 13, 14, 52, 26, 27, 37, 38, 39, 31, 32], ["p474747", 31], [32],
+                [], [], [],
+                ["和了", [-2000, -4000, 8000, -2000], [2, 2, 2, "満貫2000-4000点", "役牌 中(1飜)", "ドラ(3飜)", "赤ドラ(1飜)"]]
+            ],
+            [
+                [2, 0, 0], [22400, 23700, 32900, 25000], [41], [],
+                [], [], [], [], [], [], [], [], [], [], [], [],
+                ["流局", [0, 0, 0, 0]]
+            ]
+        ]
+    }"#;
+
+    #[test]
+    fn rebuild_wins() {
+        let wins = parse(LOG).unwrap();
+        assert_eq!(wins.len(), 2);
+
+        let ron = &wins[0];
+        assert_eq!(ron.round, "East 1");
+        assert_eq!((ron.winner, ron.loser), (1, Some(0)));
+        assert_eq!(ron.tiles, vec!["123456789m23455p"]);
+        assert_eq!(
+            (ron.win.as_str(), ron.seat.as_str(), ron.dora),
+            ("5p", "2z", 0)
+        );
+        assert_eq!(ron.logged.han, Some(2));
+        assert_eq!(ron.logged.fu, Some(40));
+        assert_eq!(ron.logged.points, 2600);
+
+        let tsumo = &wins[1];
+        assert_eq!(tsumo.round, "East 2");
+        assert_eq!((tsumo.winner, tsumo.loser), (2, None));
+        assert_eq!(tsumo.tiles, vec!["234m067p11789s", "p777z"]);
+        assert_eq!(
+            (tsumo.win.as_str(), tsumo.seat.as_str(), tsumo.dora),
+            ("1s", "2z", 3)
+        );
+        assert_eq!((tsumo.logged.han, tsumo.logged.fu), (Some(5), None));
+        assert_eq!(tsumo.logged.points, 8000);
+        assert_eq!(tsumo.logged.yaku, vec!["役牌 中", "ドラ", "赤ドラ"]);
+    }
+
+    #[test]
+    fn verify_wins() {
+        let wins = parse(LOG).unwrap();
+        assert_eq!(wins[0].verify(), vec![]);
+        assert_eq!(wins[1].verify(), vec![]);
+
+        let mut wrong = wins[0].clone();
+        wrong.logged.points = 3900;
+        wrong.logged.han = Some(3);
+        assert_eq!(
+            wrong.verify(),
+            vec![
+                Discrepancy::Han {
+                    logged: 3,
+                    scored: 2
+                },
+                Discrepancy::Points {
+                    logged: 3900,
+                    scored: 2600
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_calls_and_scores() {
+        assert_eq!(
+            Entry::from_value(&Value::from("47p4747")).unwrap(),
+            Entry::Call('p', 1, vec![47, 47, 47])
+        );
+        assert_eq!(
+            Entry::from_value(&Value::from("r60")).unwrap(),
+            Entry::Tile(60)
+        );
+        assert!(Entry::from_value(&Value::from("x1")).is_err());
+
+        let score = logged_score("30符2飜1000点∀", &["門前清自摸和(1飜)", "平和(1飜)"]).unwrap();
+        assert_eq!(
+            (score.han, score.fu, score.points),
+            (Some(2), Some(30), 3000)
+        );
+        let score = logged_score("役満32000点", &["大三元(役満)"]).unwrap();
+        assert_eq!((score.han, score.fu, score.points), (None, None, 32000));
+        assert!(logged_score("満貫", &[]).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! HTTP server scoring hands sent as json, answering with the same json as `--json`.
+
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::panic::{self, UnwindSafe};
+
+use clap::Parser;
+use mahc::hand::error::HandErr;
+use mahc::notation;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::{evaluate, json_error, json_failure, usage_message, Args};
+
+/// Arguments that read files, which requests can't use.
+const FILE_ARGUMENTS: [&str; 5] = ["file", "csv", "tsv", "tenhou_log", "majsoul_log"];
+
+/// Largest request body read, in bytes.
+const MAX_BODY_LEN: u64 = 1 << 20;
+
+/// Status code and json body of a response.
+type Reply = (u16, Value);
+
+/// Serve requests on the port of localhost until the process is stopped.
+pub fn run(port: u16) -> io::Result<()> {
+    let server = Server::http(("127.0.0.1", port)).map_err(io::Error::other)?;
+    println!("Listening on http://{}", server.server_addr());
+    serve(&server);
+    Ok(())
+}
+
+/// Answer every request, until the server is unblocked.
+fn serve(server: &Server) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY_LEN + 1)
+            .read_to_string(&mut body);
+        let (status, value) = match read {
+            Ok(len) if len as u64 > MAX_BODY_LEN => failure(
+                413,
+                "body_too_large",
+                &format!("Request bodies are limited to {} bytes", MAX_BODY_LEN),
+            ),
+            Ok(_) => {
+                let method = request.method().clone();
+                let url = request.url().to_string();
+                catch_panic(|| respond(&method, &url, &body))
+            }
+            Err(e) => failure(
+                400,
+                "invalid_request",
+                &format!("Unable to read body: {}", e),
+            ),
+        };
+        let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Answer a request, replying with an error instead if answering it panics, so the server keeps running.
+fn catch_panic(answer: impl FnOnce() -> Reply + UnwindSafe) -> Reply {
+    panic::catch_unwind(answer)
+        .unwrap_or_else(|_| failure(500, "internal_error", "The request could not be answered"))
+}
+
+/// Answer a request to one of the endpoints:
+///
+/// - `POST /hand` scores a hand, given its arguments (ex: `{"tiles": "123m456p789s11222z", "riichi": true}`)
+/// - `POST /calc` scores han and fu (ex: `{"manual": [3, 30], "ba": 1}`)
+/// - `POST /waits` finds the waits of 13 tiles in the MPSZ notation (ex: `{"tiles": "123m456p789s1122z"}`)
+/// - `POST /batch` scores an array of hands or han and fu
+pub fn respond(method: &Method, url: &str, body: &str) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    if !["/hand", "/calc", "/waits", "/batch"].contains(&path) {
+        return failure(404, "not_found", &format!("No endpoint at {}", path));
+    }
+    if *method != Method::Post {
+        return failure(405, "method_not_allowed", "Endpoints only accept POST");
+    }
+    let input: Value = match serde_json::from_str(body) {
+        Ok(input) => input,
+        Err(e) => return failure(400, "invalid_json", &format!("Invalid json: {}", e)),
+    };
+    match path {
+        "/hand" => score(&input, "tiles"),
+        "/calc" => score(&input, "manual"),
+        "/waits" => waits(&input),
+        _ => batch(&input),
+    }
+}
+
+/// Reply to a request that could not be answered.
+fn failure(status: u16, code: &str, message: &str) -> Reply {
+    let error = json_failure(code, "usage", message, json!(null));
+    (status, json!({ "error": error }))
+}
+
+/// Reply with the result of scoring.
+fn result(result: Result<String, HandErr>) -> Reply {
+    match result {
+        Ok(out) => (200, serde_json::from_str(&out).unwrap()),
+        Err(e) => (422, json!({ "error": json_error(&e) })),
+    }
+}
+
+/// Score the hand or han and fu of a request, which must have the `required` argument.
+fn score(input: &Value, required: &str) -> Reply {
+    if input.get(required).is_none_or(Value::is_null) {
+        return failure(400, "invalid_request", &format!("Missing `{}`", required));
+    }
+    match args_from_json(input) {
+        Ok(args) => result(evaluate(&args)),
+        Err(reply) => reply,
+    }
+}
+
+/// Read the command line arguments from a json object, where each key is an argument
+/// (ex: `{"tiles": ["123m", "EEEw"], "win": "Ew", "tsumo": true, "robbed_kan": "ankan"}`).
+fn args_from_json(input: &Value) -> Result<Args, Reply> {
+    let Some(fields) = input.as_object() else {
+        return Err(failure(
+            400,
+            "invalid_request",
+            "Expected a json object of arguments",
+        ));
+    };
+    let mut argv = vec![OsString::from("mahc"), OsString::from("--json")];
+    for (key, value) in fields {
+        if FILE_ARGUMENTS.contains(&key.as_str()) {
+            let message = format!("`{}` reads files, which requests can't do", key);
+            return Err(failure(400, "invalid_request", &message));
+        }
+        let values = match value {
+            Value::Null | Value::Bool(false) => continue,
+            _ if key == "json" => continue,
+            Value::Bool(true) => Vec::new(),
+            Value::Array(values) => values.iter().map(argument).collect(),
+            value => vec![argument(value)],
+        };
+        let Some(values) = values.into_iter().collect::<Option<Vec<String>>>() else {
+            let message = format!("`{}` must be a string, number or array of them", key);
+            return Err(failure(400, "invalid_request", &message));
+        };
+        argv.push(format!("--{}", key.replace('_', "-")).into());
+        argv.extend(values.into_iter().map(OsString::from));
+    }
+    Args::try_parse_from(argv).map_err(|e| failure(400, "invalid_arguments", &usage_message(&e)))
+}
+
+/// Get a single argument value from json.
+fn argument(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Find the waits of the `tiles` of a request.
+fn waits(input: &Value) -> Reply {
+    let tiles: Vec<String> = match input.get("tiles") {
+        Some(Value::String(tiles)) => vec![tiles.clone()],
+        Some(Value::Array(tiles)) => tiles.iter().filter_map(argument).collect(),
+        _ => return failure(400, "invalid_request", "Missing `tiles`"),
+    };
+    let tiles: Vec<String> = tiles.iter().map(|t| notation::from_unicode(t)).collect();
+    match notation::waits(&tiles.join(" ")) {
+        Ok(waits) => (200, json!({ "tiles": tiles, "waits": waits })),
+        Err(e) => (422, json!({ "error": json_error(&e) })),
+    }
+}
+
+/// Score every hand or han and fu of an array, like file input.
+fn batch(input: &Value) -> Reply {
+    let Some(inputs) = input.as_array() else {
+        return failure(400, "invalid_request", "Expected a json array of arguments");
+    };
+    let mut succeeded = 0;
+    let results: Vec<Value> = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            let (status, mut value) = match args_from_json(input) {
+                Ok(args) => result(evaluate(&args)),
+                Err(reply) => reply,
+            };
+            if status == 200 {
+                succeeded += 1;
+                value = json!({ "result": value });
+            }
+            value["index"] = json!(index);
+            value
+        })
+        .collect();
+    let summary = json!({
+        "total" : inputs.len(),
+        "succeeded" : succeeded,
+        "failed" : inputs.len() - succeeded
+    });
+    (200, json!({ "results": results, "summary": summary }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn post(path: &str, body: &str) -> Reply {
+        respond(&Method::Post, path, body)
+    }
+
+    #[test]
+    fn score_hands_and_han() {
+        let (status, out) = post(
+            "/hand",
+            r#"{"tiles": "123m456p789s11222z", "riichi": true, "tsumo": true, "seat": "Sw"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(out["han"], 3);
+        assert_eq!(out["yakuString"][0], "Riichi: 1");
+
+        let (status, out) = post("/calc", r#"{"manual": [3, 30], "ba": 1}"#);
+        assert_eq!(status, 200);
+        assert_eq!(out["scores"]["non-dealer"]["ron"], 4200);
+
+        let (status, out) = post(
+            "/hand",
+            r#"{"tiles": ["123m", "1z3m", "EEEw", "55s"], "win": "5s"}"#,
+        );
+        assert_eq!(status, 422);
+        assert_eq!(out["error"]["code"], "invalid_group");
+    }
+
+    #[test]
+    fn bad_requests() {
+        assert_eq!(post("/hand", "{").0, 400);
+        assert_eq!(post("/hand", r#"{"win": "3m"}"#).0, 400);
+        assert_eq!(post("/calc", r#"{"manual": [3, 30], "file": "x"}"#).0, 400);
+        let (status, out) = post("/calc", r#"{"manual": [3, 30], "honba": 1}"#);
+        assert_eq!(status, 400);
+        assert_eq!(out["error"]["code"], "invalid_arguments");
+        assert_eq!(post("/score", "{}").0, 404);
+        assert_eq!(respond(&Method::Get, "/hand", "").0, 405);
+    }
+
+    #[test]
+    fn panics_are_errors() {
+        let (status, out) = catch_panic(|| panic!("bug"));
+        assert_eq!(status, 500);
+        assert_eq!(out["error"]["code"], "internal_error");
+    }
+
+    #[test]
+    fn waits_and_batch() {
+        let (status, out) = post("/waits", r#"{"tiles": "123m456p789s1122z"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(out["waits"], json!(["Ew", "Sw"]));
+
+        let tiles = json!({ "tiles": format!("{}m", "1".repeat(256)) });
+        let (status, out) = post("/waits", &tiles.to_string());
+        assert_eq!(status, 422);
+        assert_eq!(out["error"]["code"], "invalid_shape");
+
+        let (status, out) = post("/batch", r#"[{"manual": [1, 30]}, {"manual": [0, 30]}]"#);
+        assert_eq!(status, 200);
+        assert_eq!(
+            out["results"][0]["result"]["scores"]["non-dealer"]["ron"],
+            1000
+        );
+        assert_eq!(out["results"][1]["error"]["code"], "no_han");
+        assert_eq!(out["results"][1]["index"], 1);
+        assert_eq!(out["summary"]["failed"], 1);
+    }
+
+    /// Send a request to the server, returning the raw response.
+    fn send(addr: std::net::SocketAddr, path: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+        io::read_to_string(stream).unwrap()
+    }
+
+    #[test]
+    fn serve_localhost() {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let addr = server.server_addr().to_ip().unwrap();
+        let handle = std::thread::spawn({
+            let server = Arc::clone(&server);
+            move || serve(&server)
+        });
+
+        let response = send(addr, "/calc", r#"{"manual": [2, 40]}"#);
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.contains("application/json"));
+        let json = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        let out: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(out["scores"]["non-dealer"]["ron"], 2600);
+
+        let body = " ".repeat(MAX_BODY_LEN as usize + 1);
+        let response = send(addr, "/calc", &body);
+        assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+
+        server.unblock();
+        handle.join().unwrap();
+    }
+}
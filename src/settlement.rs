@@ -0,0 +1,827 @@
+use crate::calc::calculate_yakuman;
+use crate::hand::error::HandErr;
+use crate::yaku::Yaku;
+
+/// Number of players at the table.
+pub const PLAYER_COUNT: usize = 4;
+
+/// Points paid into the pot when declaring riichi.
+pub const RIICHI_DEPOSIT: i32 = 1000;
+
+/// Points paid per honba (repeat counter) on a ron.
+///
+/// On a tsumo, each paying player pays a third of this.
+pub const HONBA_RON: i32 = 300;
+
+/// Number of amounts in a payment, as returned by [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score).
+const PAYMENT_LEN: usize = 5;
+
+/// Points paid in total by the players who are not tenpai at an exhaustive draw (noten bappu).
+pub const NOTEN_PAYMENT: i32 = 3000;
+
+/// A single win to be settled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Win {
+    /// Seat index (0-3) of the winning player.
+    pub winner: usize,
+    /// Seat index (0-3) of the player who dealt in, or `None` for a tsumo.
+    pub loser: Option<usize>,
+    /// Payment amounts of the hand, calculated without any honba.
+    ///
+    /// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the `Vec`.
+    pub payment: Vec<u32>,
+    /// Player liable for part of the payment (pao), if any.
+    pub liable: Option<Liability>,
+}
+
+/// Liability (pao, or sekinin barai) of a player who fed the meld completing a yakuman.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Liability {
+    /// Seat index (0-3) of the liable player.
+    pub seat: usize,
+    /// Part of the hand payment the liable player is responsible for, calculated without any honba.
+    ///
+    /// See [`LimitHands::get_score()`](crate::limit_hand::LimitHands::get_score) for the exact format of the `Vec`.
+    pub payment: Vec<u32>,
+}
+
+impl Liability {
+    /// Create the liability of `seat` for the yakuman in `yaku` that carry pao.
+    ///
+    /// Daisangen and daisuushii always carry pao; suukantsu only does if `suukantsu` is set by the ruleset.
+    /// Returns `None` if none of the yaku carry pao.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::settlement::Liability;
+    /// use mahc::yaku::Yaku;
+    ///
+    /// let liability = Liability::new(2, &[Yaku::Daisangen, Yaku::Tsuuiisou], false).unwrap();
+    /// assert_eq!(liability.payment, vec![48000, 16000, 32000, 8000, 16000]);
+    ///
+    /// assert_eq!(Liability::new(2, &[Yaku::Suukantsu], false), None);
+    /// ```
+    pub fn new(seat: usize, yaku: &[Yaku], suukantsu: bool) -> Option<Self> {
+        let pao: Vec<Yaku> = yaku
+            .iter()
+            .filter_map(|y| match y {
+                Yaku::Daisangen => Some(Yaku::Daisangen),
+                Yaku::Daisuushii => Some(Yaku::Daisuushii),
+                Yaku::Suukantsu if suukantsu => Some(Yaku::Suukantsu),
+                _ => None,
+            })
+            .collect();
+
+        calculate_yakuman(&pao)
+            .ok()
+            .map(|payment| Self { seat, payment })
+    }
+}
+
+/// The score table after a hand has been settled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    /// New scores for each seat.
+    pub scores: [i32; PLAYER_COUNT],
+    /// Point change for each seat.
+    pub deltas: [i32; PLAYER_COUNT],
+    /// Riichi sticks left on the table after the settlement.
+    pub riichi_sticks: u16,
+}
+
+/// Settle a win, moving the hand payment, honba and riichi deposits between the players.
+///
+/// Every seat in `riichi` declared riichi this hand and pays [`RIICHI_DEPOSIT`] into the pot. The winner collects the pot along with any `riichi_sticks` already on the table.
+///
+/// If a player is [liable](Liability) for the hand, they pay the whole liable part of a tsumo as if it were a ron, along with all of the honba.
+/// On a ron, they split the liable part evenly with the discarder, who still pays the honba.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc::calculate;
+/// use mahc::settlement::{settle, Win};
+///
+/// // Seat 1 rons seat 2 for 3 han 30 fu, with 1 honba and 1 stick on the table.
+/// let win = Win {
+///     winner: 1,
+///     loser: Some(2),
+///     payment: calculate(&[3, 30], 0).unwrap(),
+///     liable: None,
+/// };
+/// let settlement = settle(
+///     [25000, 25000, 25000, 25000],
+///     0,
+///     &win,
+///     1,
+///     1,
+///     [false, true, false, false],
+/// )
+/// .unwrap();
+///
+/// assert_eq!(settlement.deltas, [0, 5200, -4200, 0]);
+/// assert_eq!(settlement.scores, [25000, 30200, 20800, 25000]);
+/// assert_eq!(settlement.riichi_sticks, 0);
+/// ```
+pub fn settle(
+    scores: [i32; PLAYER_COUNT],
+    dealer: usize,
+    win: &Win,
+    honba: u16,
+    riichi_sticks: u16,
+    riichi: [bool; PLAYER_COUNT],
+) -> Result<Settlement, HandErr> {
+    validate_seat(dealer)?;
+    validate_win(win)?;
+
+    let mut deltas = [0; PLAYER_COUNT];
+    pay_riichi_deposits(&mut deltas, riichi);
+    pay_hand(&mut deltas, dealer, win, honba);
+
+    let pot = i32::from(riichi_sticks) * RIICHI_DEPOSIT + declared_riichi(riichi) * RIICHI_DEPOSIT;
+    deltas[win.winner] += pot;
+
+    Ok(Settlement {
+        scores: apply_deltas(scores, deltas),
+        deltas,
+        riichi_sticks: 0,
+    })
+}
+
+/// How a discard won by several players is paid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiRonRule {
+    /// Only the first winner in turn order after the discarder is paid (head bump).
+    Atamahane,
+    /// Every winner is paid by the discarder.
+    AllWinners,
+}
+
+/// Ruleset options for settling double and triple ron.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiRonRules {
+    pub rule: MultiRonRule,
+    /// Each winner receives the honba payment, rather than only the first winner in turn order.
+    ///
+    /// Only used with [`MultiRonRule::AllWinners`].
+    pub honba_to_each_winner: bool,
+    /// A triple ron is an abortive draw (sanchahou) and nobody is paid.
+    pub triple_ron_draw: bool,
+}
+
+impl MultiRonRules {
+    /// Check if this many players winning off the same discard is an abortive draw rather than a win.
+    pub fn is_abortive(&self, wins: usize) -> bool {
+        wins == 3 && self.triple_ron_draw
+    }
+
+    /// Get the wins that are paid, out of the wins off the same discard, in turn order after the discarder.
+    ///
+    /// Nobody is paid on an abortive draw, and only the first winner with [`MultiRonRule::Atamahane`].
+    /// A single win is always paid, and may be a tsumo.
+    pub fn paid_wins<'a>(&self, wins: &'a [Win]) -> Result<Vec<&'a Win>, HandErr> {
+        if let [win] = wins {
+            validate_win(win)?;
+            return Ok(vec![win]);
+        }
+        let mut wins = turn_order_wins(wins)?;
+        if self.is_abortive(wins.len()) {
+            wins.clear();
+        } else if self.rule == MultiRonRule::Atamahane {
+            wins.truncate(1);
+        }
+        Ok(wins)
+    }
+}
+
+impl Default for MultiRonRules {
+    /// Tenhou rules: every winner is paid with honba, and a triple ron is an abortive draw.
+    fn default() -> Self {
+        Self {
+            rule: MultiRonRule::AllWinners,
+            honba_to_each_winner: true,
+            triple_ron_draw: true,
+        }
+    }
+}
+
+/// Settle several players winning off the same discard.
+///
+/// The riichi sticks on the table, including the deposits made this hand, go to the first winner in turn order after the discarder.
+/// On an abortive triple ron the deposits stay on the table and are returned in [`Settlement::riichi_sticks`].
+///
+/// A single win is settled the same as [`settle()`], and may be a tsumo. Several wins must all be rons off the same discard.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc::calculate;
+/// use mahc::settlement::{settle_multi_ron, MultiRonRule, MultiRonRules, Win};
+///
+/// // Seats 1 and 3 both ron seat 2's discard.
+/// let wins = [
+///     Win {
+///         winner: 1,
+///         loser: Some(2),
+///         payment: calculate(&[1, 30], 0).unwrap(),
+///         liable: None,
+///     },
+///     Win {
+///         winner: 3,
+///         loser: Some(2),
+///         payment: calculate(&[2, 30], 0).unwrap(),
+///         liable: None,
+///     },
+/// ];
+/// let rules = MultiRonRules {
+///     rule: MultiRonRule::Atamahane,
+///     ..Default::default()
+/// };
+/// let settlement = settle_multi_ron(
+///     [25000, 25000, 25000, 25000],
+///     0,
+///     &wins,
+///     0,
+///     0,
+///     [false; 4],
+///     rules,
+/// )
+/// .unwrap();
+///
+/// // Seat 3 sits next after seat 2, so it takes the head bump.
+/// assert_eq!(settlement.deltas, [0, 0, -2000, 2000]);
+/// ```
+pub fn settle_multi_ron(
+    scores: [i32; PLAYER_COUNT],
+    dealer: usize,
+    wins: &[Win],
+    honba: u16,
+    riichi_sticks: u16,
+    riichi: [bool; PLAYER_COUNT],
+    rules: MultiRonRules,
+) -> Result<Settlement, HandErr> {
+    validate_seat(dealer)?;
+    if let [win] = wins {
+        return settle(scores, dealer, win, honba, riichi_sticks, riichi);
+    }
+    let wins = turn_order_wins(wins)?;
+
+    let mut deltas = [0; PLAYER_COUNT];
+    if rules.is_abortive(wins.len()) {
+        pay_riichi_deposits(&mut deltas, riichi);
+        return Ok(Settlement {
+            scores: apply_deltas(scores, deltas),
+            deltas,
+            riichi_sticks: riichi_sticks.saturating_add(declared_riichi(riichi) as u16),
+        });
+    }
+
+    if rules.rule == MultiRonRule::Atamahane {
+        return settle(scores, dealer, wins[0], honba, riichi_sticks, riichi);
+    }
+
+    pay_riichi_deposits(&mut deltas, riichi);
+
+    for (i, win) in wins.iter().enumerate() {
+        let honba = if i == 0 || rules.honba_to_each_winner {
+            honba
+        } else {
+            0
+        };
+        pay_hand(&mut deltas, dealer, win, honba);
+    }
+
+    let pot = i32::from(riichi_sticks) * RIICHI_DEPOSIT + declared_riichi(riichi) * RIICHI_DEPOSIT;
+    deltas[wins[0].winner] += pot;
+
+    Ok(Settlement {
+        scores: apply_deltas(scores, deltas),
+        deltas,
+        riichi_sticks: 0,
+    })
+}
+
+/// Settle an exhaustive draw (ryuukyoku), where the players who are not tenpai pay [`NOTEN_PAYMENT`] to the ones who are.
+///
+/// Nothing is paid if every player or no player is tenpai. The riichi deposits made this hand stay on the table.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::settlement::settle_draw;
+///
+/// let settlement = settle_draw(
+///     [25000, 25000, 25000, 25000],
+///     [true, false, false, true],
+///     0,
+///     [true, false, false, false],
+/// );
+///
+/// assert_eq!(settlement.deltas, [500, -1500, -1500, 1500]);
+/// assert_eq!(settlement.riichi_sticks, 1);
+/// ```
+pub fn settle_draw(
+    scores: [i32; PLAYER_COUNT],
+    tenpai: [bool; PLAYER_COUNT],
+    riichi_sticks: u16,
+    riichi: [bool; PLAYER_COUNT],
+) -> Settlement {
+    let mut deltas = [0; PLAYER_COUNT];
+    pay_riichi_deposits(&mut deltas, riichi);
+
+    let tenpai_count = tenpai.iter().filter(|&&t| t).count() as i32;
+    if tenpai_count > 0 && tenpai_count < PLAYER_COUNT as i32 {
+        let noten_count = PLAYER_COUNT as i32 - tenpai_count;
+        for (delta, is_tenpai) in deltas.iter_mut().zip(tenpai) {
+            *delta += if is_tenpai {
+                NOTEN_PAYMENT / tenpai_count
+            } else {
+                -NOTEN_PAYMENT / noten_count
+            };
+        }
+    }
+
+    Settlement {
+        scores: apply_deltas(scores, deltas),
+        deltas,
+        riichi_sticks: riichi_sticks.saturating_add(declared_riichi(riichi) as u16),
+    }
+}
+
+/// Validate the wins off a single discard and sort them in turn order after the discarder.
+fn turn_order_wins(wins: &[Win]) -> Result<Vec<&Win>, HandErr> {
+    let loser = match wins.first() {
+        Some(Win {
+            loser: Some(loser), ..
+        }) => *loser,
+        _ => return Err(HandErr::InvalidMultiRon),
+    };
+
+    let mut ordered: Vec<&Win> = Vec::new();
+    for win in wins {
+        validate_win(win)?;
+        if win.loser != Some(loser) || ordered.iter().any(|w| w.winner == win.winner) {
+            return Err(HandErr::InvalidMultiRon);
+        }
+        ordered.push(win);
+    }
+    if ordered.len() >= PLAYER_COUNT {
+        return Err(HandErr::InvalidMultiRon);
+    }
+
+    ordered.sort_by_key(|win| (win.winner + PLAYER_COUNT - loser) % PLAYER_COUNT);
+    Ok(ordered)
+}
+
+/// Check that the seat index exists at the table.
+pub(crate) fn validate_seat(seat: usize) -> Result<(), HandErr> {
+    if seat >= PLAYER_COUNT {
+        return Err(HandErr::InvalidSeat);
+    }
+    Ok(())
+}
+
+/// Check that the seats and payments of a win are valid.
+pub(crate) fn validate_win(win: &Win) -> Result<(), HandErr> {
+    validate_seat(win.winner)?;
+    if win.payment.len() != PAYMENT_LEN {
+        return Err(HandErr::InvalidPayment);
+    }
+    if let Some(loser) = win.loser {
+        validate_seat(loser)?;
+        if loser == win.winner {
+            return Err(HandErr::RonFromSelf);
+        }
+    }
+    if let Some(liability) = &win.liable {
+        validate_seat(liability.seat)?;
+        if liability.seat == win.winner {
+            return Err(HandErr::LiableWinner);
+        }
+        if liability.payment.len() != PAYMENT_LEN {
+            return Err(HandErr::InvalidPayment);
+        }
+    }
+    Ok(())
+}
+
+/// Count the riichi declarations made this hand.
+pub(crate) fn declared_riichi(riichi: [bool; PLAYER_COUNT]) -> i32 {
+    riichi.iter().filter(|&&declared| declared).count() as i32
+}
+
+/// Take the riichi deposit from every seat that declared riichi this hand.
+pub(crate) fn pay_riichi_deposits(deltas: &mut [i32; PLAYER_COUNT], riichi: [bool; PLAYER_COUNT]) {
+    for (delta, declared) in deltas.iter_mut().zip(riichi) {
+        if declared {
+            *delta -= RIICHI_DEPOSIT;
+        }
+    }
+}
+
+/// Move the hand payment, including honba, from the paying seats to the winner.
+pub(crate) fn pay_hand(deltas: &mut [i32; PLAYER_COUNT], dealer: usize, win: &Win, honba: u16) {
+    let honba = i32::from(honba);
+    let is_dealer = win.winner == dealer;
+    let ron_index = if is_dealer { 0 } else { 2 };
+
+    // The liable part of the payment is taken out and paid separately.
+    let liable_payment = |index: usize| -> i32 {
+        win.liable
+            .as_ref()
+            .map_or(0, |liability| liability.payment[index] as i32)
+    };
+
+    let mut pay = |seat: usize, payment: i32| {
+        deltas[seat] -= payment;
+        deltas[win.winner] += payment;
+    };
+
+    match win.loser {
+        Some(loser) => {
+            let liable = liable_payment(ron_index);
+            let payment = win.payment[ron_index] as i32 - liable;
+            pay(loser, payment + liable / 2 + honba * HONBA_RON);
+            if let Some(liability) = &win.liable {
+                pay(liability.seat, liable / 2);
+            }
+        }
+        None => {
+            for seat in (0..PLAYER_COUNT).filter(|&seat| seat != win.winner) {
+                let index = if is_dealer {
+                    1
+                } else if seat == dealer {
+                    4
+                } else {
+                    3
+                };
+                let payment = win.payment[index] as i32 - liable_payment(index);
+                let honba = if win.liable.is_some() {
+                    0
+                } else {
+                    honba * HONBA_RON / 3
+                };
+                pay(seat, payment + honba);
+            }
+            if let Some(liability) = &win.liable {
+                pay(
+                    liability.seat,
+                    liable_payment(ron_index) + honba * HONBA_RON,
+                );
+            }
+        }
+    }
+}
+
+/// Add the point changes to the scores.
+pub(crate) fn apply_deltas(
+    scores: [i32; PLAYER_COUNT],
+    deltas: [i32; PLAYER_COUNT],
+) -> [i32; PLAYER_COUNT] {
+    let mut out = scores;
+    for (score, delta) in out.iter_mut().zip(deltas) {
+        *score += delta;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        settle, settle_draw, settle_multi_ron, Liability, MultiRonRule, MultiRonRules, Win,
+    };
+    use crate::calc::{calculate, calculate_yakuman};
+    use crate::hand::error::HandErr;
+    use crate::yaku::Yaku;
+
+    const START: [i32; 4] = [25000, 25000, 25000, 25000];
+
+    #[test]
+    fn non_dealer_ron() {
+        let win = Win {
+            winner: 2,
+            loser: Some(0),
+            payment: calculate(&[2, 30], 0).unwrap(),
+            liable: None,
+        };
+        let out = settle(START, 0, &win, 0, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [-2000, 0, 2000, 0]);
+        assert_eq!(out.scores, [23000, 25000, 27000, 25000]);
+    }
+
+    #[test]
+    fn dealer_ron_with_honba_and_sticks() {
+        let win = Win {
+            winner: 0,
+            loser: Some(3),
+            payment: calculate(&[4, 30], 0).unwrap(),
+            liable: None,
+        };
+        let out = settle(START, 0, &win, 2, 2, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [11600 + 600 + 2000, 0, 0, -12200]);
+        assert_eq!(out.riichi_sticks, 0);
+    }
+
+    #[test]
+    fn non_dealer_tsumo_with_honba() {
+        let win = Win {
+            winner: 1,
+            loser: None,
+            payment: calculate(&[1, 30], 0).unwrap(),
+            liable: None,
+        };
+        let out = settle(START, 3, &win, 1, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [-400, 1400, -400, -600]);
+    }
+
+    #[test]
+    fn dealer_tsumo_riichi() {
+        let win = Win {
+            winner: 2,
+            loser: None,
+            payment: calculate(&[5, 30], 0).unwrap(),
+            liable: None,
+        };
+        let out = settle(START, 2, &win, 0, 1, [true, false, true, false]).unwrap();
+        // 4000 all, plus the stick on the table and seat 0's deposit; seat 2's own deposit is returned.
+        assert_eq!(out.deltas, [-5000, -4000, 12000 + 2000, -4000]);
+        assert_eq!(out.scores.iter().sum::<i32>(), 100000 + 1000);
+    }
+
+    #[test]
+    fn yakuman_tsumo() {
+        let win = Win {
+            winner: 0,
+            loser: None,
+            payment: calculate_yakuman(&vec![Yaku::Daisangen]).unwrap(),
+            liable: None,
+        };
+        let out = settle(START, 1, &win, 0, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [32000, -16000, -8000, -8000]);
+    }
+
+    #[test]
+    fn invalid_seats() {
+        let win = Win {
+            winner: 4,
+            loser: Some(0),
+            payment: calculate(&[1, 30], 0).unwrap(),
+            liable: None,
+        };
+        assert_eq!(
+            settle(START, 0, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::InvalidSeat
+        );
+
+        let win = Win {
+            winner: 1,
+            loser: Some(1),
+            payment: calculate(&[1, 30], 0).unwrap(),
+            liable: None,
+        };
+        assert_eq!(
+            settle(START, 0, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::RonFromSelf
+        );
+        assert_eq!(
+            settle(START, 5, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::InvalidSeat
+        );
+    }
+
+    #[test]
+    fn invalid_payments() {
+        let mut win = Win {
+            winner: 1,
+            loser: Some(0),
+            payment: vec![1000],
+            liable: None,
+        };
+        assert_eq!(
+            settle(START, 0, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::InvalidPayment
+        );
+
+        win.payment = calculate(&[1, 30], 0).unwrap();
+        win.liable = Some(Liability {
+            seat: 2,
+            payment: vec![1000, 500],
+        });
+        assert_eq!(
+            settle(START, 0, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::InvalidPayment
+        );
+    }
+
+    fn ron(winner: usize, loser: usize, han: u16) -> Win {
+        Win {
+            winner,
+            loser: Some(loser),
+            payment: calculate(&[han, 30], 0).unwrap(),
+            liable: None,
+        }
+    }
+
+    #[test]
+    fn double_ron_all_winners() {
+        let wins = [ron(0, 1, 1), ron(3, 1, 2)];
+        let out = settle_multi_ron(
+            START,
+            0,
+            &wins,
+            1,
+            1,
+            [false, false, false, true],
+            MultiRonRules::default(),
+        )
+        .unwrap();
+        // Seat 3 is first after seat 1 so takes the sticks; both winners get honba.
+        assert_eq!(
+            out.deltas,
+            [1500 + 300, -1800 - 2300, 0, -1000 + 2300 + 2000]
+        );
+        assert_eq!(out.riichi_sticks, 0);
+    }
+
+    #[test]
+    fn double_ron_honba_to_first_winner() {
+        let wins = [ron(0, 1, 1), ron(3, 1, 2)];
+        let rules = MultiRonRules {
+            honba_to_each_winner: false,
+            ..Default::default()
+        };
+        let out = settle_multi_ron(START, 0, &wins, 1, 0, [false; 4], rules).unwrap();
+        assert_eq!(out.deltas, [1500, -1500 - 2300, 0, 2300]);
+    }
+
+    #[test]
+    fn double_ron_atamahane() {
+        let wins = [ron(2, 1, 3), ron(0, 1, 1)];
+        let rules = MultiRonRules {
+            rule: MultiRonRule::Atamahane,
+            ..Default::default()
+        };
+        let out =
+            settle_multi_ron(START, 0, &wins, 0, 0, [true, false, false, false], rules).unwrap();
+        assert_eq!(out.deltas, [-1000, -3900, 3900 + 1000, 0]);
+    }
+
+    #[test]
+    fn triple_ron() {
+        let wins = [ron(0, 2, 1), ron(1, 2, 1), ron(3, 2, 1)];
+        let out = settle_multi_ron(
+            START,
+            0,
+            &wins,
+            0,
+            2,
+            [true, false, false, false],
+            MultiRonRules::default(),
+        )
+        .unwrap();
+        assert_eq!(out.deltas, [-1000, 0, 0, 0]);
+        assert_eq!(out.riichi_sticks, 3);
+
+        let rules = MultiRonRules {
+            triple_ron_draw: false,
+            ..Default::default()
+        };
+        let out = settle_multi_ron(START, 0, &wins, 0, 2, [false; 4], rules).unwrap();
+        assert_eq!(out.deltas, [1500, 1000, -3500, 1000 + 2000]);
+
+        let rules = MultiRonRules {
+            rule: MultiRonRule::Atamahane,
+            ..Default::default()
+        };
+        let out =
+            settle_multi_ron(START, 0, &wins, 0, 2, [true, false, false, false], rules).unwrap();
+        assert_eq!(out.deltas, [-1000, 0, 0, 0]);
+        assert_eq!(out.riichi_sticks, 3);
+
+        let rules = MultiRonRules {
+            triple_ron_draw: false,
+            ..rules
+        };
+        let out = settle_multi_ron(START, 0, &wins, 0, 2, [false; 4], rules).unwrap();
+        assert_eq!(out.deltas, [0, 0, -1000, 1000 + 2000]);
+
+        let rules = MultiRonRules::default();
+        let out = settle_multi_ron(START, 0, &wins, 0, u16::MAX, [true; 4], rules).unwrap();
+        assert_eq!(out.riichi_sticks, u16::MAX);
+    }
+
+    #[test]
+    fn single_win() {
+        let win = Win {
+            winner: 1,
+            loser: None,
+            payment: calculate(&[1, 30], 0).unwrap(),
+            liable: None,
+        };
+        let out = settle_multi_ron(
+            START,
+            3,
+            std::slice::from_ref(&win),
+            1,
+            0,
+            [false; 4],
+            MultiRonRules::default(),
+        )
+        .unwrap();
+        assert_eq!(out, settle(START, 3, &win, 1, 0, [false; 4]).unwrap());
+    }
+
+    #[test]
+    fn invalid_multi_ron() {
+        let rules = MultiRonRules::default();
+        let wins = [ron(0, 2, 1), ron(1, 3, 1)];
+        assert_eq!(
+            settle_multi_ron(START, 0, &wins, 0, 0, [false; 4], rules).unwrap_err(),
+            HandErr::InvalidMultiRon
+        );
+        let wins = [ron(0, 2, 1), ron(0, 2, 1)];
+        assert_eq!(
+            settle_multi_ron(START, 0, &wins, 0, 0, [false; 4], rules).unwrap_err(),
+            HandErr::InvalidMultiRon
+        );
+        assert_eq!(
+            settle_multi_ron(START, 0, &[], 0, 0, [false; 4], rules).unwrap_err(),
+            HandErr::InvalidMultiRon
+        );
+    }
+
+    #[test]
+    fn pao_tsumo() {
+        let yaku = vec![Yaku::Daisangen];
+        let win = Win {
+            winner: 1,
+            loser: None,
+            payment: calculate_yakuman(&yaku).unwrap(),
+            liable: Liability::new(3, &yaku, false),
+        };
+        let out = settle(START, 0, &win, 2, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [0, 32600, 0, -32600]);
+    }
+
+    #[test]
+    fn pao_ron() {
+        let yaku = vec![Yaku::Daisuushii];
+        let win = Win {
+            winner: 0,
+            loser: Some(2),
+            payment: calculate_yakuman(&yaku).unwrap(),
+            liable: Liability::new(1, &yaku, false),
+        };
+        let out = settle(START, 0, &win, 1, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [48300, -24000, -24300, 0]);
+    }
+
+    #[test]
+    fn pao_double_yakuman() {
+        // Only the daisangen is liable, the tsuuiisou is paid as a regular tsumo.
+        let yaku = vec![Yaku::Daisangen, Yaku::Tsuuiisou];
+        let win = Win {
+            winner: 1,
+            loser: None,
+            payment: calculate_yakuman(&yaku).unwrap(),
+            liable: Liability::new(2, &yaku, false),
+        };
+        let out = settle(START, 0, &win, 0, 0, [false; 4]).unwrap();
+        assert_eq!(out.deltas, [-16000, 64000, -8000 - 32000, -8000]);
+    }
+
+    #[test]
+    fn pao_suukantsu_rule() {
+        let yaku = vec![Yaku::Suukantsu];
+        assert_eq!(Liability::new(2, &yaku, false), None);
+        let liability = Liability::new(2, &yaku, true).unwrap();
+        assert_eq!(liability.payment, calculate_yakuman(&yaku).unwrap());
+
+        let win = Win {
+            winner: 2,
+            loser: Some(0),
+            payment: calculate_yakuman(&yaku).unwrap(),
+            liable: Some(liability),
+        };
+        assert_eq!(
+            settle(START, 0, &win, 0, 0, [false; 4]).unwrap_err(),
+            HandErr::LiableWinner
+        );
+    }
+
+    #[test]
+    fn exhaustive_draw() {
+        let out = settle_draw(START, [false, false, true, false], 2, [false; 4]);
+        assert_eq!(out.deltas, [-1000, -1000, 3000, -1000]);
+        assert_eq!(out.riichi_sticks, 2);
+
+        let out = settle_draw(START, [true; 4], 0, [true, false, true, false]);
+        assert_eq!(out.deltas, [-1000, 0, -1000, 0]);
+        assert_eq!(out.riichi_sticks, 2);
+        assert_eq!(settle_draw(START, [false; 4], 0, [false; 4]).deltas, [0; 4]);
+
+        let out = settle_draw(START, [false; 4], u16::MAX, [true; 4]);
+        assert_eq!(out.riichi_sticks, u16::MAX);
+    }
+}
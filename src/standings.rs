@@ -0,0 +1,128 @@
+//! Final standings of a game, with placement bonuses (uma and oka).
+
+use crate::settlement::PLAYER_COUNT;
+
+/// Ruleset options for the final standings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandingsRules {
+    /// Placement bonus for first to fourth place, in thousands of points (ex: `[15, 5, -5, -15]`).
+    pub uma: [i32; PLAYER_COUNT],
+    /// Points each player started with.
+    pub starting_points: i32,
+    /// Points each player is measured against at the end (ex: 30000).
+    ///
+    /// The difference with the starting points of every player (oka) goes to first place.
+    pub returning_points: i32,
+}
+
+impl Default for StandingsRules {
+    /// Uma of +15/+5/-5/-15, starting from 25000 and returning 30000 points.
+    fn default() -> Self {
+        Self {
+            uma: [15, 5, -5, -15],
+            starting_points: 25000,
+            returning_points: 30000,
+        }
+    }
+}
+
+impl StandingsRules {
+    /// Total bonus for first place, in points.
+    pub fn oka(&self) -> i32 {
+        (self.returning_points - self.starting_points) * PLAYER_COUNT as i32
+    }
+}
+
+/// Final standing of a seat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Standing {
+    pub seat: usize,
+    /// Place, from 1 to 4.
+    pub rank: usize,
+    /// Final score of the game.
+    pub score: i32,
+    /// Final total with uma and oka, in points. Divide by 1000 for the usual total (ex: `37000` for +37.0).
+    pub points: i32,
+}
+
+/// Get the place (0 for first) of each seat, with ties going to the seat that was dealer first.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::standings::placements;
+///
+/// assert_eq!(placements(&[25000, 30000, 15000, 30000]), [2, 0, 3, 1]);
+/// ```
+pub fn placements(scores: &[i32; PLAYER_COUNT]) -> [usize; PLAYER_COUNT] {
+    let mut order: Vec<usize> = (0..PLAYER_COUNT).collect();
+    order.sort_by_key(|&seat| (std::cmp::Reverse(scores[seat]), seat));
+
+    let mut places = [0; PLAYER_COUNT];
+    for (place, seat) in order.into_iter().enumerate() {
+        places[seat] = place;
+    }
+    places
+}
+
+/// Get the final standings of a game from the final scores, ordered from first to last place.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::standings::{standings, StandingsRules};
+///
+/// let standings = standings(&[32000, 28000, 24500, 15500], &StandingsRules::default());
+///
+/// let points: Vec<i32> = standings.iter().map(|s| s.points).collect();
+/// assert_eq!(points, vec![37000, 3000, -10500, -29500]);
+/// ```
+pub fn standings(scores: &[i32; PLAYER_COUNT], rules: &StandingsRules) -> Vec<Standing> {
+    let places = placements(scores);
+    let mut out: Vec<Standing> = (0..PLAYER_COUNT)
+        .map(|seat| {
+            let place = places[seat];
+            let mut points = scores[seat] - rules.returning_points + rules.uma[place] * 1000;
+            if place == 0 {
+                points += rules.oka();
+            }
+            Standing {
+                seat,
+                rank: place + 1,
+                score: scores[seat],
+                points,
+            }
+        })
+        .collect();
+    out.sort_by_key(|standing| standing.rank);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ties_go_by_seat_order() {
+        let out = standings(&[30000, 20000, 30000, 20000], &StandingsRules::default());
+        let order: Vec<(usize, i32)> = out.iter().map(|s| (s.seat, s.points)).collect();
+        assert_eq!(order, vec![(0, 35000), (2, 5000), (1, -15000), (3, -25000)]);
+    }
+
+    #[test]
+    fn uma_without_oka() {
+        let rules = StandingsRules {
+            uma: [30, 10, -10, -30],
+            starting_points: 30000,
+            returning_points: 30000,
+        };
+        assert_eq!(rules.oka(), 0);
+        let out = standings(&[45000, -2000, 31000, 46000], &rules);
+        let points: Vec<(usize, i32)> = out.iter().map(|s| (s.seat, s.points)).collect();
+        assert_eq!(
+            points,
+            vec![(3, 46000), (0, 25000), (2, -9000), (1, -62000)]
+        );
+        assert_eq!(out.iter().map(|s| s.points).sum::<i32>(), 0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::hand::error::HandErr;
+use crate::hand::error::{HandErr, InputErr};
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub enum Suit {
@@ -30,7 +30,12 @@ impl Suit {
             "m" => Ok(Self::Manzu),
             "w" => Ok(Self::Wind),
             "d" => Ok(Self::Dragon),
-            _ => Err(HandErr::InvalidSuit),
+            _ => Err(HandErr::InvalidSuit(InputErr::new(
+                &suit,
+                0,
+                suit.chars().count(),
+                "a suit (m, p, s, w, d)",
+            ))),
         }
     }
 }
@@ -1,4 +1,5 @@
-use crate::hand::error::HandErr;
+use crate::hand::error::{HandErr, InputErr};
+use crate::notation;
 use crate::suit::Suit;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -8,22 +9,61 @@ pub struct TileGroup {
     pub isopen: bool,
     pub group_type: GroupType,
     pub isterminal: bool,
+    /// How the group was called or declared, if it is a meld.
+    pub meld: Option<Meld>,
 }
 
 impl TileGroup {
     pub fn new(group: String) -> Result<Self, HandErr> {
-        let isopen = group.chars().last().unwrap().to_string() == "o";
-        let value = group.chars().nth(0).unwrap().to_string();
+        let chars: Vec<char> = group.chars().collect();
+        let open_at = chars.iter().position(|&c| c == 'o');
+        let isopen = open_at.is_some();
 
-        let suit = if !isopen {
-            group.chars().last().unwrap().to_string()
-        } else {
-            group.chars().nth(group.len() - 2).unwrap().to_string()
+        // The suit comes right before the open marker, and there must be at least one value before the suit.
+        let suit_offset = match open_at.unwrap_or(chars.len()).checked_sub(1) {
+            Some(offset) if offset > 0 => offset,
+            _ => {
+                return Err(HandErr::InvalidGroup(InputErr::whole(
+                    &group,
+                    "a tile group (ex: 123m, EEEw, rrrdo)",
+                    &format!("`{}`", group),
+                )))
+            }
         };
-        let suit = Suit::suit_from_string(suit)?;
+        let value = chars[0].to_string();
+
+        let suit = Suit::suit_from_string(chars[suit_offset].to_string())
+            .map_err(|e| e.within(&group, suit_offset))?;
 
         let group_type = GroupType::group_type_from_string(group.to_string())?;
 
+        let suit_values = match suit {
+            Suit::Manzu | Suit::Pinzu | Suit::Souzu => "123456789",
+            Suit::Wind => "ESWN",
+            Suit::Dragon => "rgw",
+        };
+        if let Some(offset) = chars[..suit_offset]
+            .iter()
+            .position(|&c| !suit_values.contains(c))
+        {
+            return Err(HandErr::InvalidGroup(InputErr::new(
+                &group,
+                offset,
+                1,
+                "a tile value matching the suit (1-9 for m, p, s; E, S, W, N for w; r, g, w for d)",
+            )));
+        }
+
+        let meld = match open_at {
+            Some(open_at) => Meld::parse(&group, open_at, &group_type)?,
+            None if group_type == GroupType::Kan => Some(Meld {
+                kind: MeldKind::Ankan,
+                from: None,
+                claimed: None,
+            }),
+            None => None,
+        };
+
         let mut isterminal = false;
         if group_type == GroupType::Sequence {
             if value == "1" || value == "7" {
@@ -39,6 +79,7 @@ impl TileGroup {
             isopen,
             group_type,
             isterminal,
+            meld,
         };
 
         Ok(tile)
@@ -49,12 +90,195 @@ impl TileGroup {
         matches!(self.suit, Suit::Wind | Suit::Dragon)
     }
 
+    /// Get the individual tiles of the group, written in the single tile notation (ex: `123m` gives `1m`, `2m`, `3m`).
+    pub fn tiles(&self) -> Vec<String> {
+        let suit = match self.suit {
+            Suit::Manzu => "m",
+            Suit::Pinzu => "p",
+            Suit::Souzu => "s",
+            Suit::Wind => "w",
+            Suit::Dragon => "d",
+        };
+
+        match self.group_type {
+            GroupType::Sequence => {
+                let start = self.parse_u8().unwrap_or_default();
+                (start..start + 3)
+                    .map(|i| format!("{}{}", i, suit))
+                    .collect()
+            }
+            GroupType::Triplet => vec![format!("{}{}", self.value, suit); 3],
+            GroupType::Kan => vec![format!("{}{}", self.value, suit); 4],
+            GroupType::Pair => vec![format!("{}{}", self.value, suit); 2],
+            GroupType::None => vec![format!("{}{}", self.value, suit)],
+        }
+    }
+
+    /// Render the group as Unicode mahjong tiles (ex: `123m` gives 🀇🀈🀉), with the outer tiles of a closed kan face down.
+    pub fn to_unicode(&self) -> String {
+        let mut tiles: Vec<char> = self
+            .tiles()
+            .iter()
+            .filter_map(|tile| notation::to_unicode_tile(tile).ok())
+            .collect();
+        if self.group_type == GroupType::Kan && !self.isopen {
+            let last = tiles.len() - 1;
+            tiles[0] = notation::UNICODE_BACK;
+            tiles[last] = notation::UNICODE_BACK;
+        }
+        tiles.into_iter().collect()
+    }
+
+    /// Check that the group can be played in sanma (three-player mahjong).
+    ///
+    /// Sanma is played without the 2-8 manzu tiles, and chii calls are not allowed.
+    pub fn validate_sanma(&self) -> Result<(), HandErr> {
+        if self.isopen && self.group_type == GroupType::Sequence {
+            return Err(HandErr::SanmaChii);
+        }
+
+        if self.suit == Suit::Manzu
+            && (self.group_type == GroupType::Sequence || !(self.value == "1" || self.value == "9"))
+        {
+            return Err(HandErr::SanmaInvalidTile);
+        }
+
+        Ok(())
+    }
+
     /// Parse the group value into an integer.
     pub fn parse_u8(&self) -> Result<u8, std::num::ParseIntError> {
         self.value.parse()
     }
 }
 
+/// The kind of a called or declared meld.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeldKind {
+    Chi,
+    Pon,
+    /// Kan called from a discard (open kan).
+    Daiminkan,
+    /// Kan made by adding a tile to a pon (added kan), which can be robbed by chankan.
+    Shouminkan,
+    /// Kan declared from concealed tiles (closed kan).
+    Ankan,
+}
+
+/// The player a meld was called from, relative to the player who called it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// The player to the left (kamicha), who discards just before.
+    Left,
+    /// The player across (toimen).
+    Across,
+    /// The player to the right (shimocha).
+    Right,
+}
+
+impl Source {
+    /// Get the seat index (0-3) of the source, given the seat index of the player who called the meld.
+    pub fn seat(&self, caller: usize) -> usize {
+        match self {
+            Self::Left => (caller + 3) % 4,
+            Self::Across => (caller + 2) % 4,
+            Self::Right => (caller + 1) % 4,
+        }
+    }
+}
+
+/// Details of a called or declared meld.
+///
+/// Melds are written after the suit of the group: `o` for an open meld, then `+` if it is an added kan,
+/// then `L`, `A` or `R` for the player it was called from (left, across or right), then the value of the claimed tile.
+/// For example `345moL4` is a chi of 4m from the left, `EEEEwo+A` is an added kan to a pon called from across,
+/// and `5555p` is a closed kan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meld {
+    pub kind: MeldKind,
+    /// The player the meld was called from, if known. Closed kans are never called.
+    pub from: Option<Source>,
+    /// The tile that was claimed, in the single tile notation (ex: `4m`), if known.
+    pub claimed: Option<String>,
+}
+
+impl Meld {
+    /// Parse the meld details written after the open marker at `open_at` in the `group`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::tile_group::{MeldKind, Source, TileGroup};
+    ///
+    /// let group = TileGroup::new("345moL4".to_string()).unwrap();
+    /// let meld = group.meld.unwrap();
+    ///
+    /// assert_eq!(meld.kind, MeldKind::Chi);
+    /// assert_eq!(meld.from, Some(Source::Left));
+    /// assert_eq!(meld.claimed, Some("4m".to_string()));
+    /// ```
+    fn parse(group: &str, open_at: usize, group_type: &GroupType) -> Result<Option<Self>, HandErr> {
+        let chars: Vec<char> = group.chars().collect();
+        let values = &chars[..open_at - 1];
+        let suit = chars[open_at - 1];
+        let invalid = |offset: usize, expected: &str| {
+            HandErr::InvalidGroup(InputErr::new(group, offset, 1, expected))
+        };
+
+        let mut at = open_at + 1;
+        let added = chars.get(at) == Some(&'+');
+        if added {
+            at += 1;
+        }
+        let from = match chars.get(at) {
+            Some('L') => Some(Source::Left),
+            Some('A') => Some(Source::Across),
+            Some('R') => Some(Source::Right),
+            _ => None,
+        };
+        if from.is_some() {
+            at += 1;
+        }
+        let claimed = match chars.get(at) {
+            Some(value) if values.contains(value) => Some(format!("{}{}", value, suit)),
+            Some(_) => {
+                return Err(invalid(
+                    at,
+                    "a call detail (+ for an added kan, L/A/R for the player called from, or the claimed tile from the group)",
+                ))
+            }
+            None => None,
+        };
+        if claimed.is_some() {
+            at += 1;
+        }
+        if at < chars.len() {
+            return Err(invalid(at, "the end of the group"));
+        }
+
+        let kind = match (group_type, added) {
+            (GroupType::Sequence, false) => MeldKind::Chi,
+            (GroupType::Triplet, false) => MeldKind::Pon,
+            (GroupType::Kan, false) => MeldKind::Daiminkan,
+            (GroupType::Kan, true) => MeldKind::Shouminkan,
+            (GroupType::Pair | GroupType::None, false) if at == open_at + 1 => return Ok(None),
+            (GroupType::Sequence | GroupType::Triplet, true) => {
+                return Err(invalid(open_at + 1, "an added kan (+) to be a kan"))
+            }
+            _ => return Err(invalid(open_at, "a call to be a sequence, triplet or kan")),
+        };
+        if kind == MeldKind::Chi && from.is_some_and(|from| from != Source::Left) {
+            return Err(invalid(open_at + 1, "a chi to be called from the left (L)"));
+        }
+
+        Ok(Some(Self {
+            kind,
+            from,
+            claimed,
+        }))
+    }
+}
+
 //AHAHAHAHAHAHAHAH I DONT NEED THIS
 //turns our i did need this :)
 #[derive(Debug, Clone, PartialEq)]
@@ -81,43 +305,71 @@ impl GroupType {
     /// assert_eq!(actual, expected);
     /// ```
     pub fn group_type_from_string(group: String) -> Result<Self, HandErr> {
-        let count = if group.contains('o') {
-            group.len() - 2
-        } else {
-            group.len() - 1
-        };
+        let chars: Vec<char> = group.chars().collect();
+        let count = chars
+            .iter()
+            .position(|&c| c == 'o')
+            .unwrap_or(chars.len())
+            .saturating_sub(1);
+        let sub_group: String = chars[..count].iter().collect();
 
-        if let Some(sub_group) = group.get(0..count) {
-            for i in sub_group.chars() {
-                if !"123456789ESWNrgw".contains(i) {
-                    return Err(HandErr::InvalidGroup);
-                }
+        if sub_group.is_empty() {
+            return Err(HandErr::InvalidGroup(InputErr::whole(
+                &group,
+                "a tile group",
+                &format!("`{}`", group),
+            )));
+        }
+
+        for (offset, i) in sub_group.chars().enumerate() {
+            if !"123456789ESWNrgw".contains(i) {
+                return Err(HandErr::InvalidGroup(InputErr::new(
+                    &group,
+                    offset,
+                    1,
+                    "a tile value (1-9, E, S, W, N, r, g, w)",
+                )));
             }
-        } else {
-            return Err(HandErr::InvalidGroup);
+        }
+
+        if (count == 2 || count == 4) && sub_group.chars().any(|c| c != chars[0]) {
+            return Err(HandErr::InvalidGroup(InputErr::new(
+                &group,
+                0,
+                count,
+                "a pair or kan of the same tile",
+            )));
         }
 
         match count {
             2 => Ok(Self::Pair),
             3 => {
-                if group.chars().nth(0).unwrap() == group.chars().nth(1).unwrap()
-                    && group.chars().nth(1).unwrap() == group.chars().nth(2).unwrap()
-                {
+                if chars[0] == chars[1] && chars[1] == chars[2] {
                     Ok(Self::Triplet)
                 } else if ["123", "234", "345", "456", "567", "678", "789"]
                     .iter()
                     .cloned()
                     .collect::<std::collections::HashSet<&str>>()
-                    .contains(group.get(0..count).unwrap())
+                    .contains(sub_group.as_str())
                 {
-                    return Ok(Self::Sequence);
+                    Ok(Self::Sequence)
                 } else {
-                    return Err(HandErr::InvalidGroup);
+                    Err(HandErr::InvalidGroup(InputErr::new(
+                        &group,
+                        0,
+                        count,
+                        "a triplet or a sequence in order",
+                    )))
                 }
             }
             4 => Ok(Self::Kan),
             1 => Ok(Self::None),
-            _ => Err(HandErr::InvalidGroup),
+            _ => Err(HandErr::InvalidGroup(InputErr::new(
+                &group,
+                0,
+                count,
+                "1 to 4 tiles",
+            ))),
         }
     }
 }
@@ -0,0 +1,352 @@
+//! Terminal UI to build a hand from a tile palette and see its score as it changes.
+
+use std::io;
+
+use clap::Parser;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::{default_hand_out, score_any_hand, Args, Notation};
+
+/// Honor tiles as labelled in the palette, in tile index order.
+const HONORS: [&str; 7] = ["Ew", "Sw", "Ww", "Nw", "wd", "gd", "rd"];
+
+/// Seat and prevalent winds, in the hand notation.
+const WINDS: [&str; 4] = ["Ew", "Sw", "Ww", "Nw"];
+
+/// Number of different tiles in the palette.
+const TILE_COUNT: usize = 34;
+
+/// Tiles on each row of the palette.
+const ROW_LENGTH: usize = 9;
+
+const HELP: &str = "arrows: move  enter: add tile  backspace: remove tile  c/p/k/a: chi/pon/kan/closed kan  x: remove call  s/w: seat/prevalent wind  +/-: dora  q: quit";
+
+/// Situational flags, with the key that toggles them.
+const FLAGS: [(char, &str); 8] = [
+    ('t', "tsumo"),
+    ('r', "riichi"),
+    ('d', "double riichi"),
+    ('i', "ippatsu"),
+    ('h', "haitei"),
+    ('n', "rinshan"),
+    ('g', "chankan"),
+    ('e', "tenhou"),
+];
+
+/// State of the hand being built.
+#[derive(Debug, Clone)]
+pub struct App {
+    /// Tile index selected in the palette.
+    cursor: usize,
+    /// Concealed tiles in the order they were added, the last one being the winning tile.
+    closed: Vec<usize>,
+    /// Called melds, as their call marker and lowest tile.
+    calls: Vec<(char, usize)>,
+    /// Arguments holding the round context and flags.
+    args: Args,
+    quit: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let mut args = Args::parse_from(["mahc"]);
+        args.notation = Notation::Mpsz;
+        Self {
+            cursor: 0,
+            closed: Vec::new(),
+            calls: Vec::new(),
+            args,
+            quit: false,
+        }
+    }
+}
+
+/// Get a tile in the MPSZ notation (ex: `5p`, `7z`).
+fn mpsz_tile(tile: usize) -> String {
+    let suit = ['m', 'p', 's', 'z'][tile / ROW_LENGTH];
+    format!("{}{}", tile % ROW_LENGTH + 1, suit)
+}
+
+/// Get the label of a tile in the palette.
+fn label(tile: usize) -> String {
+    if tile >= 3 * ROW_LENGTH {
+        HONORS[tile - 3 * ROW_LENGTH].to_string()
+    } else {
+        mpsz_tile(tile)
+    }
+}
+
+/// Check if a chi can start from a tile, which must be suited with a value of 7 at most.
+fn starts_chi(tile: usize) -> bool {
+    tile < 3 * ROW_LENGTH && tile % ROW_LENGTH < 7
+}
+
+impl App {
+    /// Handle a key press.
+    pub fn handle_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(TILE_COUNT - 1),
+            KeyCode::Up => self.cursor = self.cursor.saturating_sub(ROW_LENGTH),
+            KeyCode::Down => self.cursor = (self.cursor + ROW_LENGTH).min(TILE_COUNT - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.closed.push(self.cursor),
+            KeyCode::Backspace => {
+                self.closed.pop();
+            }
+            KeyCode::Char('c') if !starts_chi(self.cursor) => {}
+            KeyCode::Char(marker @ ('c' | 'p' | 'k' | 'a')) => {
+                self.calls.push((marker, self.cursor))
+            }
+            KeyCode::Char('x') => {
+                self.calls.pop();
+            }
+            KeyCode::Char('s') => self.args.seat = next_wind(&self.args.seat),
+            KeyCode::Char('w') => self.args.prev = next_wind(&self.args.prev),
+            KeyCode::Char('+') => self.args.dora = self.args.dora.saturating_add(1),
+            KeyCode::Char('-') => self.args.dora = self.args.dora.saturating_sub(1),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(key) => {
+                if let Some(flag) = self.flag_mut(key) {
+                    *flag = !*flag;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the flag toggled by a key.
+    fn flag_mut(&mut self, key: char) -> Option<&mut bool> {
+        let args = &mut self.args;
+        match key {
+            't' => Some(&mut args.tsumo),
+            'r' => Some(&mut args.riichi),
+            'd' => Some(&mut args.doubleriichi),
+            'i' => Some(&mut args.ippatsu),
+            'h' => Some(&mut args.haitei),
+            'n' => Some(&mut args.rinshan),
+            'g' => Some(&mut args.chankan),
+            'e' => Some(&mut args.tenhou),
+            _ => None,
+        }
+    }
+
+    /// Get the hand in the MPSZ notation, as it would be given to `--tiles`.
+    pub fn tiles(&self) -> Vec<String> {
+        let closed: String = self.closed.iter().map(|&tile| mpsz_tile(tile)).collect();
+        let calls = self.calls.iter().map(|&(marker, tile)| {
+            let digit = tile % ROW_LENGTH + 1;
+            let digits: String = match marker {
+                'c' => (digit..digit + 3).map(|d| d.to_string()).collect(),
+                'p' => digit.to_string().repeat(3),
+                _ => digit.to_string().repeat(4),
+            };
+            format!(
+                "{}{}{}",
+                marker,
+                digits,
+                ['m', 'p', 's', 'z'][tile / ROW_LENGTH]
+            )
+        });
+        std::iter::once(closed)
+            .filter(|closed| !closed.is_empty())
+            .chain(calls)
+            .collect()
+    }
+
+    /// Score the hand, or explain why it can't be scored.
+    pub fn score(&self) -> String {
+        if self.closed.is_empty() {
+            return "Add tiles to the hand".to_string();
+        }
+        let mut args = self.args.clone();
+        args.tiles = Some(self.tiles());
+        match score_any_hand(&args) {
+            Ok((args, result)) => default_hand_out(result, &args).trim_start().to_string(),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [palette, hand, flags, score, help] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+
+        let rows: Vec<Line> = (0..TILE_COUNT)
+            .collect::<Vec<usize>>()
+            .chunks(ROW_LENGTH)
+            .map(|row| {
+                let spans = row.iter().map(|&tile| {
+                    let style = if tile == self.cursor {
+                        Style::new().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::new()
+                    };
+                    Span::styled(format!(" {} ", label(tile)), style)
+                });
+                Line::from(spans.collect::<Vec<Span>>())
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(rows).block(Block::bordered().title("Tiles")),
+            palette,
+        );
+
+        let title = format!(
+            "Hand | seat {} | prevalent {} | dora {}",
+            self.args.seat, self.args.prev, self.args.dora
+        );
+        frame.render_widget(
+            Paragraph::new(self.tiles().join(" ")).block(Block::bordered().title(title)),
+            hand,
+        );
+
+        let mut flag_spans = Vec::new();
+        for (key, name) in FLAGS {
+            let on = self.flag_mut(key).is_some_and(|flag| *flag);
+            let mark = if on { 'x' } else { ' ' };
+            flag_spans.push(Span::raw(format!("[{}] {} ({})  ", mark, name, key)));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(flag_spans))
+                .wrap(Wrap { trim: true })
+                .block(Block::bordered().title("Flags")),
+            flags,
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.score()).block(Block::bordered().title("Score")),
+            score,
+        );
+        frame.render_widget(Paragraph::new(HELP).wrap(Wrap { trim: true }), help);
+    }
+}
+
+/// Get the wind after this one, going back to east after north.
+fn next_wind(wind: &str) -> String {
+    let index = WINDS
+        .iter()
+        .position(|w| *w == wind)
+        .unwrap_or(WINDS.len() - 1);
+    WINDS[(index + 1) % WINDS.len()].to_string()
+}
+
+/// Run the terminal UI until it is quit.
+pub fn run() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut app = App::default();
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| app.draw(frame)) {
+            break Err(e);
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => app.handle_key(key.code),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+        if app.quit {
+            break Ok(());
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, keys: &str) {
+        for key in keys.chars() {
+            app.handle_key(KeyCode::Char(key));
+        }
+    }
+
+    /// Move the cursor to a tile and add it.
+    fn add(app: &mut App, tile: usize) {
+        app.cursor = tile;
+        app.handle_key(KeyCode::Enter);
+    }
+
+    #[test]
+    fn build_and_score() {
+        let mut app = App::default();
+        assert_eq!(app.score(), "Add tiles to the hand");
+
+        // 123m 456p 789s 22z 55z, winning on 5z
+        for tile in [0, 1, 2, 12, 13, 14, 24, 25, 26, 28, 28, 31, 31, 31] {
+            add(&mut app, tile);
+        }
+        assert_eq!(app.tiles(), vec!["1m2m3m4p5p6p7s8s9s2z2z5z5z5z"]);
+        press(&mut app, "rt");
+        let score = app.score();
+        assert!(score.starts_with("3 Han/"), "{}", score);
+        assert!(score.contains("Riichi: 1"), "{}", score);
+        assert!(score.contains("MenzenTsumo: 1"), "{}", score);
+
+        press(&mut app, "r");
+        app.handle_key(KeyCode::Backspace);
+        assert!(app.score().contains("Invalid"), "{}", app.score());
+    }
+
+    #[test]
+    fn calls_and_cursor() {
+        let mut app = App::default();
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.cursor, 10);
+        press(&mut app, "c");
+        app.cursor = 33;
+        press(&mut app, "pk");
+        press(&mut app, "x");
+        assert_eq!(app.tiles(), vec!["c234p", "p777z"]);
+
+        // no chi from an honor, an 8 or a 9
+        press(&mut app, "c");
+        app.cursor = 7;
+        press(&mut app, "c");
+        assert_eq!(app.tiles(), vec!["c234p", "p777z"]);
+        app.cursor = 33;
+
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.cursor, 33);
+        press(&mut app, "ssssw+");
+        assert_eq!(
+            (app.args.seat.as_str(), app.args.prev.as_str()),
+            ("Ew", "Sw")
+        );
+        assert_eq!(app.args.dora, 1);
+    }
+
+    #[test]
+    fn draw_palette_and_score() {
+        let mut app = App::default();
+        for tile in [0, 1, 2, 12, 13, 14, 24, 25, 26, 28, 28, 31, 31, 31] {
+            add(&mut app, tile);
+        }
+        let backend = ratatui::backend::TestBackend::new(100, 30);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains(" 1m  2m "));
+        assert!(screen.contains(" Ew  Sw  Ww  Nw  wd  gd  rd "));
+        assert!(screen.contains("1m2m3m4p5p6p7s8s9s2z2z5z5z5z"));
+        assert!(screen.contains("1 Han/ 40 Fu"));
+    }
+}
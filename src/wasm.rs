@@ -0,0 +1,189 @@
+//! WebAssembly bindings, to score hands from JavaScript.
+//!
+//! Errors are thrown as JavaScript `Error`s with the message of the [`HandErr`].
+
+use wasm_bindgen::prelude::*;
+
+use crate::calc;
+use crate::fu::Fu;
+use crate::hand::error::HandErr;
+use crate::yaku::Yaku;
+
+fn js_error(e: HandErr) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// A hand checked to be a complete winning hand.
+#[wasm_bindgen(js_name = Hand)]
+pub struct Hand {
+    hand: crate::hand::Hand,
+}
+
+#[wasm_bindgen(js_class = Hand)]
+impl Hand {
+    /// Read the hand tiles in the hand notation (ex: `["123m", "456p", "789s", "EEEw", "22m"]`), with the winning tile and winds.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        tiles: Vec<String>,
+        win: String,
+        prev: String,
+        seat: String,
+    ) -> Result<Hand, JsError> {
+        crate::hand::Hand::new(tiles, win, prev, seat)
+            .map(|hand| Self { hand })
+            .map_err(js_error)
+    }
+
+    /// Whether the hand has any open melds.
+    #[wasm_bindgen(getter, js_name = isOpen)]
+    pub fn is_open(&self) -> bool {
+        self.hand.is_open()
+    }
+
+    /// Render the hand as Unicode mahjong tiles.
+    #[wasm_bindgen(js_name = toUnicode)]
+    pub fn to_unicode(&self) -> String {
+        self.hand.to_unicode()
+    }
+}
+
+/// Score breakdown of a hand, as returned by [`get_hand_score()`].
+#[wasm_bindgen]
+pub struct Score {
+    payments: Vec<u32>,
+    yaku: Vec<String>,
+    fu: Vec<String>,
+    han: u16,
+    fu_total: u16,
+    open: bool,
+}
+
+#[wasm_bindgen]
+impl Score {
+    /// Payment amounts, in the format of [`calculate()`].
+    #[wasm_bindgen(getter)]
+    pub fn payments(&self) -> Vec<u32> {
+        self.payments.clone()
+    }
+
+    /// Each yaku with its han (ex: `Riichi: 1`).
+    #[wasm_bindgen(getter)]
+    pub fn yaku(&self) -> Vec<String> {
+        self.yaku.clone()
+    }
+
+    /// Each part of the fu (ex: `Tsumo: 2`).
+    #[wasm_bindgen(getter, js_name = fuDetails)]
+    pub fn fu_details(&self) -> Vec<String> {
+        self.fu.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn han(&self) -> u16 {
+        self.han
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fu(&self) -> u16 {
+        self.fu_total
+    }
+
+    /// Whether the hand has any open melds.
+    #[wasm_bindgen(getter, js_name = isOpen)]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+impl From<(Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool)> for Score {
+    fn from(
+        (payments, yaku, fu, han_fu, open): (Vec<u32>, Vec<Yaku>, Vec<Fu>, Vec<u16>, bool),
+    ) -> Self {
+        Self {
+            payments,
+            yaku: yaku.iter().map(|y| y.to_string(open)).collect(),
+            fu: fu.iter().map(|f| f.to_string()).collect(),
+            han: han_fu[0],
+            fu_total: han_fu[1],
+            open,
+        }
+    }
+}
+
+/// Get the score breakdown of the hand. See [`calc::get_hand_score()`].
+#[wasm_bindgen(js_name = getHandScore)]
+#[allow(clippy::too_many_arguments)]
+pub fn get_hand_score(
+    tiles: Vec<String>,
+    win: String,
+    dora: u16,
+    seat: String,
+    prev: String,
+    tsumo: bool,
+    riichi: bool,
+    doubleriichi: bool,
+    ippatsu: bool,
+    haitei: bool,
+    rinshan: bool,
+    chankan: bool,
+    tenhou: bool,
+    honba: u16,
+) -> Result<Score, JsError> {
+    calc::get_hand_score(
+        tiles,
+        win,
+        dora,
+        seat,
+        prev,
+        tsumo,
+        riichi,
+        doubleriichi,
+        ippatsu,
+        haitei,
+        rinshan,
+        chankan,
+        tenhou,
+        honba,
+    )
+    .map(Score::from)
+    .map_err(js_error)
+}
+
+/// Calculate the payment amounts from the han, fu and honba. See [`calc::calculate()`].
+#[wasm_bindgen]
+pub fn calculate(han: u16, fu: u16, honba: u16) -> Result<Vec<u32>, JsError> {
+    calc::calculate(&[han, fu], honba).map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_from_hand() {
+        let tiles = ["123m", "456p", "789s", "EEEw", "22m"]
+            .map(String::from)
+            .to_vec();
+        let score = get_hand_score(
+            tiles,
+            "2m".to_string(),
+            1,
+            "Ew".to_string(),
+            "Ew".to_string(),
+            true,
+            true,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+        )
+        .unwrap_or_else(|_| panic!("the hand should score"));
+        assert_eq!(score.han(), 5);
+        assert_eq!(score.yaku()[0], "Riichi: 1");
+        assert_eq!(score.payments(), vec![12000, 4000, 8000, 2000, 4000]);
+        assert!(!score.is_open());
+    }
+}